@@ -0,0 +1,264 @@
+use std::ops::AddAssign;
+use std::hash::Hash;
+use std::io::Write;
+use std::io;
+use super::frequency_analysis::frequency_analysis;
+use super::frequency_analysis::Frequencies;
+use crate::languages::Language;
+use crate::languages::LANGUAGES;
+use crate::output::OutputFormat;
+use crate::output::write_json_float;
+use crate::output::write_json_string;
+use crate::scoring::LanguageModel;
+
+/// Chi-squared statistic of the input letters against one language
+#[derive(PartialEq, Debug, Clone)]
+pub struct LanguageChiSquared {
+	pub language: String,
+	pub chi_squared: f64,
+}
+
+/// Count the ASCII letters A to Z, case insensitive
+pub fn frequencies_to_letter_counts<
+	T: Copy + Eq + Hash + From<u8>
+>(map: &Frequencies<T>) -> [usize; 26] {
+	let mut result = [0; 26];
+	for (i, count) in result.iter_mut().enumerate() {
+		let upper = T::from(b'A' + i as u8);
+		let lower = T::from(b'a' + i as u8);
+		*count = map.get(&upper).unwrap_or(&0) + map.get(&lower).unwrap_or(&0);
+	}
+	return result;
+}
+
+/// Chi-squared statistic of letter counts against the letter distribution of
+/// a language, fails without letters
+pub fn letter_chi_squared(
+		counts: &[usize; 26], language: &Language) -> Result<f64, io::Error> {
+	return probabilities_chi_squared(counts, &language.letter_probabilities());
+}
+
+/// Chi-squared statistic of letter counts against letter probabilities,
+/// fails without letters
+pub fn probabilities_chi_squared(
+		counts: &[usize; 26], probabilities: &[f64; 26]) -> Result<f64, io::Error> {
+	let total: usize = counts.iter().sum();
+	if total == 0 {
+		return Err(io::Error::other("Cannot calculate chi-squared without letters"));
+	}
+	let mut result: f64 = 0.0;
+	for (count, p) in counts.iter().zip(probabilities.iter()) {
+		let expected = (total as f64) * p;
+		let diff = (*count as f64) - expected;
+		result += diff * diff / expected;
+	}
+	return Ok(result);
+}
+
+// Share of letters and of non-printable words in english text, the rest are
+// spaces and punctuation
+const LETTER_SHARE: f64 = 0.8;
+const NON_PRINTABLE_SHARE: f64 = 0.001;
+
+/// Printable ASCII including tab, line feed and carriage return
+pub fn is_printable(value: u128) -> bool {
+	return (0x20..0x7f).contains(&value) || value == 0x09 || value == 0x0a || value == 0x0d;
+}
+
+fn chi_squared_term(count: usize, expected: f64) -> f64 {
+	let diff = (count as f64) - expected;
+	return diff * diff / expected;
+}
+
+/// Chi-squared statistic of a text against a language, with the letters,
+/// other printable ASCII and non-printable words as categories.
+/// Unlike letter_chi_squared, texts with few letters score badly.
+pub fn text_chi_squared<
+	T: Copy + AddAssign + Eq + Hash + From<u8> + Into<u128>
+>(vec: &[T], language: &Language) -> f64 {
+	if vec.is_empty() {
+		return f64::INFINITY;
+	}
+	let counts = frequencies_to_letter_counts(&frequency_analysis(vec));
+	let total = vec.len() as f64;
+	let letters: usize = counts.iter().sum();
+	let non_printable = vec.iter().filter(|w| !is_printable((**w).into())).count();
+	let mut result: f64 = 0.0;
+	for (count, p) in counts.iter().zip(language.letter_probabilities().iter()) {
+		result += chi_squared_term(*count, total * LETTER_SHARE * p);
+	}
+	result += chi_squared_term(
+		vec.len() - letters - non_printable,
+		total * (1.0 - LETTER_SHARE - NON_PRINTABLE_SHARE));
+	result += chi_squared_term(non_printable, total * NON_PRINTABLE_SHARE);
+	return result;
+}
+
+/// Compare the letters of the input with every built-in language and the
+/// given language models, best matching language first
+pub fn chi_squared<
+	T: Copy + AddAssign + Eq + Hash + From<u8>
+>(vec: &[T], models: &Vec<LanguageModel>) -> Result<Vec<LanguageChiSquared>, io::Error> {
+	let counts = frequencies_to_letter_counts(&frequency_analysis(vec));
+	let mut result: Vec<LanguageChiSquared> = Vec::with_capacity(LANGUAGES.len() + models.len());
+	for language in LANGUAGES {
+		result.push(LanguageChiSquared {
+			language: language.name.to_string(),
+			chi_squared: letter_chi_squared(&counts, language)?,
+		});
+	}
+	for model in models {
+		result.push(LanguageChiSquared {
+			language: model.name.clone(),
+			chi_squared: probabilities_chi_squared(&counts, &model.letter_probabilities()?)?,
+		});
+	}
+	result.sort_by(|a, b| a.chi_squared.total_cmp(&b.chi_squared));
+	return Ok(result);
+}
+
+/// Write the result of chi_squared in the given format
+pub fn print_chi_squared_result<
+	W: Write
+>(result: &Vec<LanguageChiSquared>, format: &OutputFormat, out: &mut W)
+		-> Result<(), io::Error> {
+	match format {
+		OutputFormat::Text => {
+			for r in result {
+				writeln!(out, "{}: {:.6}", r.language, r.chi_squared)?;
+			}
+			if let Some(best) = result.first() {
+				writeln!(out, "Best match: {}", best.language)?;
+			}
+		},
+		OutputFormat::Json => {
+			write!(out, "{{\"chi_squared\":[")?;
+			for (k, r) in result.iter().enumerate() {
+				if k > 0 {
+					write!(out, ",")?;
+				}
+				write!(out, "{{\"language\":")?;
+				write_json_string(&r.language, out)?;
+				write!(out, ",\"chi_squared\":")?;
+				write_json_float(r.chi_squared, out)?;
+				write!(out, "}}")?;
+			}
+			write!(out, "]")?;
+			if let Some(best) = result.first() {
+				write!(out, ",\"best_match\":")?;
+				write_json_string(&best.language, out)?;
+			}
+			writeln!(out, "}}")?;
+		},
+		OutputFormat::Csv => {
+			writeln!(out, "language,chi_squared")?;
+			for r in result {
+				writeln!(out, "{},{}", r.language, r.chi_squared)?;
+			}
+		},
+	};
+	return Ok(());
+}
+
+#[cfg(test)]
+mod tests {
+	use std::io;
+	use crate::analytics::chi_squared::chi_squared;
+	use crate::analytics::chi_squared::frequencies_to_letter_counts;
+	use crate::analytics::chi_squared::letter_chi_squared;
+	use crate::analytics::chi_squared::text_chi_squared;
+	use crate::analytics::chi_squared::print_chi_squared_result;
+	use crate::analytics::chi_squared::LanguageChiSquared;
+	use crate::analytics::frequency_analysis::frequency_analysis;
+	use crate::languages::ENGLISH;
+	use crate::scoring::LanguageModel;
+	use crate::scoring::Alphabet;
+	use std::io::Write;
+	use crate::output::OutputFormat;
+
+	#[test]
+	fn frequencies_to_letter_counts_u16() {
+		let vec: Vec<u16> = "aAz! Zb".bytes().map(u16::from).collect();
+		let counts = frequencies_to_letter_counts(&frequency_analysis(&vec));
+		assert_eq!(counts[0], 2);
+		assert_eq!(counts[1], 1);
+		assert_eq!(counts[25], 2);
+		assert_eq!(counts.iter().sum::<usize>(), 5);
+	}
+
+	#[test]
+	fn letter_chi_squared_expected() -> Result<(), io::Error> {
+		// Counts exactly matching the distribution score 0
+		let p = ENGLISH.letter_probabilities();
+		let mut counts = [0; 26];
+		for (i, c) in counts.iter_mut().enumerate() {
+			*c = (p[i] * 1e9).round() as usize;
+		}
+		assert!(letter_chi_squared(&counts, &ENGLISH)? < 1e-3);
+		return Ok(());
+	}
+
+	#[test]
+	fn text_chi_squared_few_letters() {
+		let text: Vec<u8> = b"We shall fight on the beaches, we shall fight on the \
+			landing grounds, we shall fight in the fields and in the streets".to_vec();
+		let few_letters: Vec<u8> = b"We 12 34 56 78 the 90, +- 12 34 56 78 the 90 34 \
+			56 -12 34, 56 78 90 12 34 56 78 he 90 +- in 12 34 56 78 90 12 34".to_vec();
+		assert!(text_chi_squared(&text, &ENGLISH) < text_chi_squared(&few_letters, &ENGLISH));
+		assert_eq!(text_chi_squared::<u8>(&[], &ENGLISH), f64::INFINITY);
+	}
+
+	#[test]
+	fn chi_squared_errors() {
+		let vec: Vec<u8> = b"1234 !".to_vec();
+		let err = chi_squared(&vec, &vec![]).unwrap_err();
+		assert_eq!(err.kind(), io::ErrorKind::Other);
+		assert_eq!(err.to_string(), "Cannot calculate chi-squared without letters");
+		let model = LanguageModel::from_words("logs", Alphabet::Printable, b"GET /");
+		let err = chi_squared(b"abc", &vec![model]).unwrap_err();
+		assert_eq!(
+			err.to_string(),
+			"Language model logs is a model of the printable characters, not of the letters");
+	}
+
+	#[test]
+	fn chi_squared_languages() -> Result<(), io::Error> {
+		let english: Vec<u8> = b"It is a truth universally acknowledged, that a \
+			single man in possession of a good fortune, must be in want of a \
+			wife. However little known the feelings or views of such a man may \
+			be on his first entering a neighbourhood".to_vec();
+		assert_eq!(chi_squared(&english, &vec![])?[0].language, "english");
+		let german: Vec<u8> = b"Als Gregor Samsa eines Morgens aus unruhigen \
+			Traeumen erwachte, fand er sich in seinem Bett zu einem ungeheueren \
+			Ungeziefer verwandelt. Er lag auf seinem panzerartig harten Ruecken \
+			und sah, wenn er den Kopf ein wenig hob, seinen gewoelbten, braunen, \
+			von bogenfoermigen Versteifungen geteilten Bauch".to_vec();
+		assert_eq!(chi_squared(&german, &vec![])?[0].language, "german");
+		// A model trained on the text itself matches better than german
+		let model = LanguageModel::from_text("kafka", &german);
+		let result = chi_squared(&german, &vec![model])?;
+		assert_eq!(result[0].language, "kafka");
+		assert_eq!(result.len(), 5);
+		return Ok(());
+	}
+
+	#[test]
+	fn print_chi_squared_result_test() {
+		let result = vec![
+			LanguageChiSquared { language: "english".to_string(), chi_squared: 1.5 },
+			LanguageChiSquared { language: "german".to_string(), chi_squared: 20.0 }];
+		let mut out = Vec::new();
+		print_chi_squared_result(&result, &OutputFormat::Text, &mut out).unwrap();
+		print_chi_squared_result(&result, &OutputFormat::Json, &mut out).unwrap();
+		print_chi_squared_result(&result, &OutputFormat::Csv, &mut out).unwrap();
+		let mut expected = Vec::new();
+		writeln!(expected, "english: 1.500000\ngerman: 20.000000\nBest match: english").unwrap();
+		writeln!(
+			expected,
+			"{{\"chi_squared\":[{{\"language\":\"english\",\"chi_squared\":1.5}},\
+			{{\"language\":\"german\",\"chi_squared\":20}}],\"best_match\":\"english\"}}"
+		).unwrap();
+		writeln!(expected, "language,chi_squared\nenglish,1.5\ngerman,20").unwrap();
+		assert_eq!(out, expected);
+	}
+}
@@ -0,0 +1,262 @@
+use std::convert::TryFrom;
+use std::ops::AddAssign;
+use std::hash::Hash;
+use std::io::Write;
+use std::io;
+use super::frequency_analysis::frequency_analysis;
+use super::frequency_analysis::Frequencies;
+use super::frequency_analysis::frequency_analysis_chunks;
+use crate::types::NumBytes;
+use crate::output::OutputFormat;
+use crate::output::write_json_float;
+
+/// Shannon entropy of a word distribution in bits per word,
+/// fails on empty input
+pub fn frequencies_to_entropy<T>(map: &Frequencies<T>) -> Result<f64, io::Error> {
+	let total: usize = map.values().sum();
+	if total == 0 {
+		return Err(io::Error::other("Cannot calculate entropy on empty input"));
+	}
+	let mut result: f64 = 0.0;
+	for count in map.values() {
+		let p = (*count as f64) / (total as f64);
+		result -= p * p.log2();
+	}
+	return Ok(result);
+}
+
+/// Shannon entropy of the input in bits per word
+pub fn entropy<
+	T: Copy + AddAssign + Eq + Hash
+>(vec: &[T]) -> Result<f64, io::Error> {
+	return frequencies_to_entropy(&frequency_analysis(vec));
+}
+
+/// Shannon entropy of all chunks in bits per word
+pub fn entropy_chunks<
+	T: Copy + Eq + Hash + Into<u128> + TryFrom<u128> + NumBytes,
+	I: Iterator<Item = Result<Vec<T>, io::Error>>
+>(chunks: I) -> Result<f64, io::Error> {
+	return frequencies_to_entropy(&frequency_analysis_chunks(chunks)?);
+}
+
+/// Entropy of one window of the input
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct EntropyWindow {
+	/// Index of the first word of the window
+	pub start: usize,
+	pub entropy: f64,
+}
+
+/// Entropy of every window of window_size words, moved by step words
+pub fn entropy_profile<
+	T: Copy + AddAssign + Eq + Hash
+>(vec: &[T], window_size: usize, step: usize) -> Result<Vec<EntropyWindow>, io::Error> {
+	if window_size == 0 || step == 0 {
+		return Err(io::Error::other("Window size and step need to be > 0"));
+	}
+	let mut result: Vec<EntropyWindow> = Vec::new();
+	if vec.len() < window_size {
+		return Ok(result);
+	}
+	for start in (0..vec.len() - window_size + 1).step_by(step) {
+		let window = vec[start..start + window_size].to_vec();
+		result.push(EntropyWindow { start: start, entropy: entropy(&window)? });
+	}
+	return Ok(result);
+}
+
+/// Like entropy_profile, only the words of the current window and chunk are
+/// kept in memory
+pub fn entropy_profile_chunks<
+	T: Copy + AddAssign + Eq + Hash, I: Iterator<Item = Result<Vec<T>, io::Error>>
+>(chunks: I, window_size: usize, step: usize) -> Result<Vec<EntropyWindow>, io::Error> {
+	if window_size == 0 || step == 0 {
+		return Err(io::Error::other("Window size and step need to be > 0"));
+	}
+	let mut result: Vec<EntropyWindow> = Vec::new();
+	let mut buf: Vec<T> = Vec::new();
+	// Index of the first word of buf and start of the next window
+	let mut offset: usize = 0;
+	let mut start: usize = 0;
+	for chunk in chunks {
+		buf.extend(chunk?);
+		while start + window_size <= offset + buf.len() {
+			let s = start - offset;
+			let window = buf[s..s + window_size].to_vec();
+			result.push(EntropyWindow { start: start, entropy: entropy(&window)? });
+			start += step;
+		}
+		let consumed = std::cmp::min(start - offset, buf.len());
+		buf.drain(..consumed);
+		offset += consumed;
+	}
+	return Ok(result);
+}
+
+/// Write the result of entropy in the given format
+pub fn print_entropy_result<
+	W: Write
+>(result: f64, format: &OutputFormat, out: &mut W) -> Result<(), io::Error> {
+	match format {
+		OutputFormat::Text => {
+			writeln!(out, "Entropy: {:.6} bits", result)?;
+		},
+		OutputFormat::Json => {
+			write!(out, "{{\"entropy\":")?;
+			write_json_float(result, out)?;
+			writeln!(out, "}}")?;
+		},
+		OutputFormat::Csv => {
+			writeln!(out, "entropy\n{}", result)?;
+		},
+	};
+	return Ok(());
+}
+
+/// Write the result of entropy_profile in the given format
+pub fn print_entropy_profile<
+	W: Write
+>(result: &Vec<EntropyWindow>, format: &OutputFormat, out: &mut W)
+		-> Result<(), io::Error> {
+	match format {
+		OutputFormat::Text => {
+			writeln!(out, "Windows: {}", result.len())?;
+			for w in result {
+				writeln!(out, "{}: {:.6}", w.start, w.entropy)?;
+			}
+		},
+		OutputFormat::Json => {
+			write!(out, "{{\"windows\":[")?;
+			for (k, w) in result.iter().enumerate() {
+				if k > 0 {
+					write!(out, ",")?;
+				}
+				write!(out, "{{\"start\":{},\"entropy\":", w.start)?;
+				write_json_float(w.entropy, out)?;
+				write!(out, "}}")?;
+			}
+			writeln!(out, "]}}")?;
+		},
+		OutputFormat::Csv => {
+			writeln!(out, "start,entropy")?;
+			for w in result {
+				writeln!(out, "{},{}", w.start, w.entropy)?;
+			}
+		},
+	};
+	return Ok(());
+}
+
+#[cfg(test)]
+mod tests {
+	use std::io;
+	use crate::analytics::entropy::entropy;
+	use crate::analytics::entropy::entropy_profile;
+	use crate::analytics::entropy::entropy_chunks;
+	use crate::analytics::entropy::entropy_profile_chunks;
+	use crate::analytics::entropy::EntropyWindow;
+	use crate::analytics::entropy::print_entropy_result;
+	use crate::analytics::entropy::print_entropy_profile;
+	use std::io::Write;
+	use crate::output::OutputFormat;
+
+	#[test]
+	fn entropy_empty() {
+		let err = entropy::<u8>(&[]).unwrap_err();
+		assert_eq!(err.kind(), io::ErrorKind::Other);
+		assert_eq!(err.to_string(), "Cannot calculate entropy on empty input");
+	}
+
+	#[test]
+	fn entropy_u8() -> Result<(), io::Error> {
+		assert_eq!(entropy::<u8>(&[7, 7, 7])?, 0.0);
+		assert_eq!(entropy::<u8>(&[1, 2, 3, 4, 1, 2, 3, 4])?, 2.0);
+		let vec_u8: Vec<u8> = (0..=255).collect();
+		assert_eq!(entropy(&vec_u8)?, 8.0);
+		return Ok(());
+	}
+
+	#[test]
+	fn entropy_u32() -> Result<(), io::Error> {
+		let vec_u32: Vec<u32> = vec![684631, 2, 684631, 2];
+		assert_eq!(entropy(&vec_u32)?, 1.0);
+		return Ok(());
+	}
+
+	#[test]
+	fn entropy_profile_u8() -> Result<(), io::Error> {
+		let vec_u8: Vec<u8> = vec![0, 0, 0, 0, 1, 2, 3, 4];
+		assert_eq!(
+			entropy_profile(&vec_u8, 4, 2)?,
+			vec![EntropyWindow { start: 0, entropy: 0.0 },
+			     EntropyWindow { start: 2, entropy: 1.5 },
+			     EntropyWindow { start: 4, entropy: 2.0 }]);
+		assert_eq!(entropy_profile(&vec_u8, 9, 1)?, vec![]);
+		return Ok(());
+	}
+
+	#[test]
+	fn entropy_chunks_u8() -> Result<(), io::Error> {
+		let chunks: Vec<Result<Vec<u8>, io::Error>> = vec![Ok(vec![1, 2]), Ok(vec![3, 4])];
+		assert_eq!(entropy_chunks(chunks.into_iter())?, 2.0);
+		return Ok(());
+	}
+
+	#[test]
+	fn entropy_profile_chunks_matches_profile() -> Result<(), io::Error> {
+		let vec: Vec<u8> = (0..100_u8).map(|i| i % 7 + i / 30).collect();
+		for (window, step) in [(4, 1), (5, 3), (3, 7), (200, 1)] {
+			let chunks = vec.chunks(9).map(|c| Ok(c.to_vec()));
+			assert_eq!(
+				entropy_profile_chunks(chunks, window, step)?,
+				entropy_profile(&vec, window, step)?);
+		}
+		let chunks = vec.chunks(9).map(|c| Ok(c.to_vec()));
+		let err = entropy_profile_chunks(chunks, 0, 1).unwrap_err();
+		assert_eq!(err.to_string(), "Window size and step need to be > 0");
+		return Ok(());
+	}
+
+	#[test]
+	fn entropy_profile_errors() {
+		let vec_u8: Vec<u8> = vec![0, 1];
+		for (window, step) in [(0, 1), (1, 0)] {
+			let err = entropy_profile(&vec_u8, window, step).unwrap_err();
+			assert_eq!(err.kind(), io::ErrorKind::Other);
+			assert_eq!(err.to_string(), "Window size and step need to be > 0");
+		}
+	}
+
+	#[test]
+	fn print_entropy_result_test() {
+		let mut out = Vec::new();
+		print_entropy_result(1.5, &OutputFormat::Text, &mut out).unwrap();
+		print_entropy_result(1.5, &OutputFormat::Json, &mut out).unwrap();
+		print_entropy_result(1.5, &OutputFormat::Csv, &mut out).unwrap();
+		let mut expected = Vec::new();
+		writeln!(expected, "Entropy: 1.500000 bits").unwrap();
+		writeln!(expected, "{{\"entropy\":1.5}}").unwrap();
+		writeln!(expected, "entropy\n1.5").unwrap();
+		assert_eq!(out, expected);
+	}
+
+	#[test]
+	fn print_entropy_profile_test() {
+		let profile = vec![
+			EntropyWindow { start: 0, entropy: 0.0 },
+			EntropyWindow { start: 2, entropy: 1.5 }];
+		let mut out = Vec::new();
+		print_entropy_profile(&profile, &OutputFormat::Text, &mut out).unwrap();
+		print_entropy_profile(&profile, &OutputFormat::Json, &mut out).unwrap();
+		print_entropy_profile(&profile, &OutputFormat::Csv, &mut out).unwrap();
+		let mut expected = Vec::new();
+		writeln!(expected, "Windows: 2\n0: 0.000000\n2: 1.500000").unwrap();
+		writeln!(
+			expected,
+			"{{\"windows\":[{{\"start\":0,\"entropy\":0}},{{\"start\":2,\"entropy\":1.5}}]}}"
+		).unwrap();
+		writeln!(expected, "start,entropy\n0,0\n2,1.5").unwrap();
+		assert_eq!(out, expected);
+	}
+}
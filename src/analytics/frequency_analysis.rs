@@ -1,35 +1,196 @@
 use std::collections::HashMap;
+use std::convert::TryFrom;
 use std::ops::AddAssign;
 use std::hash::Hash;
 use std::fmt::Display;
 use std::io::Write;
 use std::io;
+use crate::output::write_text_list;
+use crate::output::OutputFormat;
+use crate::output::write_json_array;
+use crate::output::write_csv_list;
+use crate::types::NumBytes;
 
-pub fn frequency_analysis<T: Copy + AddAssign + Eq + Hash>(vec: &Vec<T>) -> HashMap<T, usize> {
-	let mut map: HashMap<T, usize> = HashMap::new();
-	for i in vec {
-		*map.entry(*i).or_insert(0) += 1
+/// Number of occurences of every word
+pub type Frequencies<T> = HashMap<T, usize>;
+
+fn count_words<K: Eq + Hash, I: Iterator<Item = K>>(iter: I) -> Frequencies<K> {
+	let mut map: Frequencies<K> = HashMap::new();
+	for i in iter {
+		*map.entry(i).or_insert(0) += 1
 	}
 	return map;
 }
 
+/// Counts words in a table with a bucket for every value for words up to
+/// 2 bytes, larger words are counted in a HashMap
+pub struct FrequencyCounter<T> {
+	dense: Vec<usize>,
+	map: Frequencies<T>,
+}
+
+impl<T: Copy + Eq + Hash + Into<u128> + TryFrom<u128> + NumBytes> FrequencyCounter<T> {
+	pub fn new() -> FrequencyCounter<T> {
+		let dense = match T::BYTES {
+			1 | 2 => vec![0; 1 << (8 * usize::from(T::BYTES))],
+			_ => Vec::new(),
+		};
+		return FrequencyCounter {
+			dense: dense,
+			map: HashMap::new(),
+		};
+	}
+
+	/// Count every word of the vector
+	pub fn add_all(&mut self, vec: &[T]) {
+		if self.dense.is_empty() {
+			for w in vec {
+				*self.map.entry(*w).or_insert(0) += 1;
+			}
+		} else {
+			for w in vec {
+				self.dense[(*w).into() as usize] += 1;
+			}
+		}
+	}
+
+	/// Number of occurences of every counted word
+	pub fn frequencies(mut self) -> Frequencies<T> {
+		for (i, count) in self.dense.iter().enumerate() {
+			if *count == 0 {
+				continue;
+			}
+			if let Ok(w) = T::try_from(i as u128) {
+				self.map.insert(w, *count);
+			}
+		}
+		return self.map;
+	}
+}
+
+impl<T: Copy + Eq + Hash + Into<u128> + TryFrom<u128> + NumBytes> Default for FrequencyCounter<T> {
+	fn default() -> FrequencyCounter<T> {
+		return FrequencyCounter::new();
+	}
+}
+
+/// Count the occurences of every word of all chunks
+pub fn frequency_analysis_chunks<
+	T: Copy + Eq + Hash + Into<u128> + TryFrom<u128> + NumBytes,
+	I: Iterator<Item = Result<Vec<T>, io::Error>>
+>(chunks: I) -> Result<Frequencies<T>, io::Error> {
+	let mut counter: FrequencyCounter<T> = FrequencyCounter::new();
+	for chunk in chunks {
+		counter.add_all(&chunk?);
+	}
+	return Ok(counter.frequencies());
+}
+
+/// Count the occurences of every word, FrequencyCounter is faster for words
+/// up to 2 bytes
+pub fn frequency_analysis<T: Copy + AddAssign + Eq + Hash>(vec: &[T]) -> Frequencies<T> {
+	return count_words(vec.iter().copied());
+}
+
+/// Count the occurences of every n-gram of n words. Overlapping n-grams
+/// start at every word, otherwise the input is split into n-grams.
+pub fn ngram_frequency_analysis<
+	T: Copy + Eq + Hash
+>(vec: &[T], n: usize, overlapping: bool) -> Result<Frequencies<Vec<T>>, io::Error> {
+	if n == 0 {
+		return Err(io::Error::other("N-gram size needs to be > 0"));
+	}
+	let step = if overlapping { 1 } else { n };
+	return Ok(count_words(vec.windows(n).step_by(step).map(|w| w.to_vec())));
+}
+
+/// Write the result of frequency_analysis in the given format, most frequent
+/// words first
 pub fn print_frequency_analysis_result<
 	T: Display, W: Write
->(map: HashMap<T, usize>, out: &mut W) -> Result<(), io::Error> {
+>(map: Frequencies<T>, format: &OutputFormat, out: &mut W) -> Result<(), io::Error> {
 	let mut vec: Vec<(&T, &usize)> = map.iter().collect();
 	vec.sort_by(|a, b| b.1.cmp(a.1));
-	for (i, j) in vec {
-		writeln!(out, "{}: {}", j, i)?;
-	}
+	match format {
+		OutputFormat::Text => {
+			for (i, j) in vec {
+				writeln!(out, "{}: {}", j, i)?;
+			}
+		},
+		OutputFormat::Json => {
+			write!(out, "{{\"frequencies\":[")?;
+			for (k, (i, j)) in vec.iter().enumerate() {
+				if k > 0 {
+					write!(out, ",")?;
+				}
+				write!(out, "{{\"value\":{},\"count\":{}}}", i, j)?;
+			}
+			writeln!(out, "]}}")?;
+		},
+		OutputFormat::Csv => {
+			let total: usize = map.values().sum();
+			writeln!(out, "value,count,percentage")?;
+			for (i, j) in vec {
+				let percentage = (*j as f64) * 100.0 / (total as f64);
+				writeln!(out, "{},{},{:.4}", i, j, percentage)?;
+			}
+		},
+	};
+	return Ok(());
+}
+
+/// Write the result of ngram_frequency_analysis in the given format, most
+/// frequent n-grams first
+pub fn print_ngram_frequency_analysis_result<
+	T: Display, W: Write
+>(map: &Frequencies<Vec<T>>, format: &OutputFormat, out: &mut W) -> Result<(), io::Error> {
+	let mut vec: Vec<(&Vec<T>, &usize)> = map.iter().collect();
+	vec.sort_by(|a, b| b.1.cmp(a.1));
+	match format {
+		OutputFormat::Text => {
+			for (i, j) in vec {
+				write!(out, "{}: [", j)?;
+				write_text_list(i, out)?;
+				writeln!(out, "]")?;
+			}
+		},
+		OutputFormat::Json => {
+			write!(out, "{{\"frequencies\":[")?;
+			for (k, (i, j)) in vec.iter().enumerate() {
+				if k > 0 {
+					write!(out, ",")?;
+				}
+				write!(out, "{{\"value\":")?;
+				write_json_array(i, out)?;
+				write!(out, ",\"count\":{}}}", j)?;
+			}
+			writeln!(out, "]}}")?;
+		},
+		OutputFormat::Csv => {
+			let total: usize = map.values().sum();
+			writeln!(out, "value,count,percentage")?;
+			for (i, j) in vec {
+				let percentage = (*j as f64) * 100.0 / (total as f64);
+				write_csv_list(i, out)?;
+				writeln!(out, ",{},{:.4}", j, percentage)?;
+			}
+		},
+	};
 	return Ok(());
 }
 
 #[cfg(test)]
 mod tests {
+	use std::io;
 	use crate::analytics::frequency_analysis::frequency_analysis;
+	use crate::analytics::frequency_analysis::FrequencyCounter;
+	use crate::analytics::frequency_analysis::frequency_analysis_chunks;
+	use crate::analytics::frequency_analysis::ngram_frequency_analysis;
 	use crate::analytics::frequency_analysis::print_frequency_analysis_result;
+	use crate::analytics::frequency_analysis::print_ngram_frequency_analysis_result;
 	use std::collections::HashMap;
 	use std::io::Write;
+	use crate::output::OutputFormat;
 
 	#[test]
 	fn frequency_analysis_u8() {
@@ -51,20 +212,121 @@ mod tests {
 			  (3562, 1), (1253, 1)].iter().cloned().collect());
 	}
 
+	#[test]
+	fn frequency_counter_dense() {
+		let vec_u8: Vec<u8> = vec![223, 3, 17, 223, 255, 42, 3, 17, 223];
+		let mut counter_u8 = FrequencyCounter::new();
+		counter_u8.add_all(&vec_u8);
+		counter_u8.add_all(&[0, 255]);
+		assert_eq!(
+			counter_u8.frequencies(),
+			[(223, 3), (3, 2), (17, 2), (255, 2), (42, 1), (0, 1)].iter().cloned().collect());
+		let vec_u16: Vec<u16> = vec![65535, 0, 256, 65535];
+		let mut counter_u16 = FrequencyCounter::new();
+		counter_u16.add_all(&vec_u16);
+		assert_eq!(counter_u16.frequencies(), frequency_analysis(&vec_u16));
+	}
+
+	#[test]
+	fn frequency_counter_map() {
+		let vec_u64: Vec<u64> = vec![12743, 684631, 547, 12743];
+		let mut counter: FrequencyCounter<u64> = FrequencyCounter::default();
+		counter.add_all(&vec_u64);
+		assert_eq!(counter.frequencies(), frequency_analysis(&vec_u64));
+		assert_eq!(FrequencyCounter::<u32>::new().frequencies(), HashMap::new());
+	}
+
+	#[test]
+	fn frequency_analysis_chunks_u8() -> Result<(), io::Error> {
+		let chunks: Vec<Result<Vec<u8>, io::Error>> = vec![Ok(vec![3, 17]), Ok(vec![3])];
+		assert_eq!(
+			frequency_analysis_chunks(chunks.into_iter())?,
+			[(3, 2), (17, 1)].iter().cloned().collect());
+		return Ok(());
+	}
+
 	#[test]
 	fn print_frequency_analysis_result_test() {
 		let vec: Vec<(u32, usize)> = vec![(684, 4), (2, 1), (242, 5), (2, 1), (123, 3)];
 		let mut out = Vec::new();
-		print_frequency_analysis_result(vec.iter().cloned().collect(), &mut out).unwrap();
+		print_frequency_analysis_result(
+			vec.iter().cloned().collect(), &OutputFormat::Text, &mut out).unwrap();
 		let mut expected = Vec::new();
 		writeln!(expected, "5: 242\n4: 684\n3: 123\n1: 2").unwrap();
 		assert_eq!(out, expected);
 	}
 
+	#[test]
+	fn print_frequency_analysis_result_json() {
+		let vec: Vec<(u32, usize)> = vec![(684, 4), (242, 5)];
+		let mut out = Vec::new();
+		print_frequency_analysis_result(
+			vec.iter().cloned().collect(), &OutputFormat::Json, &mut out).unwrap();
+		let mut expected = Vec::new();
+		writeln!(
+			expected,
+			"{{\"frequencies\":[{{\"value\":242,\"count\":5}},{{\"value\":684,\"count\":4}}]}}"
+		).unwrap();
+		assert_eq!(out, expected);
+	}
+
 	#[test]
 	fn frequency_analysis_empty() {
 		assert_eq!(
 			frequency_analysis::<u128>(&Vec::new()),
 			HashMap::new());
 	}
+
+	#[test]
+	fn print_frequency_analysis_result_csv() {
+		let vec: Vec<(u32, usize)> = vec![(684, 3), (242, 5)];
+		let mut out = Vec::new();
+		print_frequency_analysis_result(
+			vec.iter().cloned().collect(), &OutputFormat::Csv, &mut out).unwrap();
+		let mut expected = Vec::new();
+		writeln!(expected, "value,count,percentage\n242,5,62.5000\n684,3,37.5000").unwrap();
+		assert_eq!(out, expected);
+	}
+
+	#[test]
+	fn ngram_frequency_analysis_overlapping() -> Result<(), io::Error> {
+		let result = ngram_frequency_analysis(&b"THETHEN"[..], 3, true)?;
+		assert_eq!(
+			result,
+			[(b"THE".to_vec(), 2), (b"HET".to_vec(), 1), (b"ETH".to_vec(), 1),
+			 (b"HEN".to_vec(), 1)].iter().cloned().collect());
+		return Ok(());
+	}
+
+	#[test]
+	fn ngram_frequency_analysis_non_overlapping() -> Result<(), io::Error> {
+		let result = ngram_frequency_analysis(&[1_u16, 2, 1, 2, 2, 1, 7], 2, false)?;
+		assert_eq!(
+			result,
+			[(vec![1, 2], 2), (vec![2, 1], 1)].iter().cloned().collect());
+		assert_eq!(ngram_frequency_analysis(&[1_u8], 2, true)?, HashMap::new());
+		let err = ngram_frequency_analysis(&[1_u8], 0, true).unwrap_err();
+		assert_eq!(err.to_string(), "N-gram size needs to be > 0");
+		return Ok(());
+	}
+
+	#[test]
+	fn print_ngram_frequency_analysis_result_test() {
+		let map: HashMap<Vec<u8>, usize> =
+			[(vec![84, 72], 3), (vec![72, 69], 1)].iter().cloned().collect();
+		let mut out = Vec::new();
+		print_ngram_frequency_analysis_result(&map, &OutputFormat::Text, &mut out).unwrap();
+		print_ngram_frequency_analysis_result(&map, &OutputFormat::Json, &mut out).unwrap();
+		print_ngram_frequency_analysis_result(&map, &OutputFormat::Csv, &mut out).unwrap();
+		let mut expected = Vec::new();
+		writeln!(expected, "3: [84, 72]\n1: [72, 69]").unwrap();
+		writeln!(
+			expected,
+			"{{\"frequencies\":[{{\"value\":[84,72],\"count\":3}},\
+			{{\"value\":[72,69],\"count\":1}}]}}").unwrap();
+		writeln!(
+			expected,
+			"value,count,percentage\n84 72,3,75.0000\n72 69,1,25.0000").unwrap();
+		assert_eq!(out, expected);
+	}
 }
@@ -0,0 +1,115 @@
+use std::ops::AddAssign;
+use std::hash::Hash;
+use std::io::Write;
+use std::io;
+use super::frequency_analysis::frequency_analysis;
+use super::frequency_analysis::Frequencies;
+use crate::output::OutputFormat;
+use crate::output::write_json_float;
+
+/// Probability that two randomly chosen words of the input are equal,
+/// fails on less than 2 words
+pub fn frequencies_to_index_of_coincidence<T>(
+		map: &Frequencies<T>) -> Result<f64, io::Error> {
+	let total: usize = map.values().sum();
+	if total < 2 {
+		return Err(io::Error::other(
+			"Cannot calculate index of coincidence on less than 2 words"));
+	}
+	let mut sum: f64 = 0.0;
+	for count in map.values() {
+		sum += (*count as f64) * ((*count as f64) - 1.0);
+	}
+	return Ok(sum / ((total as f64) * ((total as f64) - 1.0)));
+}
+
+/// Index of coincidence of the words of the input
+pub fn index_of_coincidence<
+	T: Copy + AddAssign + Eq + Hash
+>(vec: &[T]) -> Result<f64, io::Error> {
+	return frequencies_to_index_of_coincidence(&frequency_analysis(vec));
+}
+
+/// Write the result of index_of_coincidence in the given format
+pub fn print_index_of_coincidence_result<
+	W: Write
+>(result: f64, format: &OutputFormat, out: &mut W) -> Result<(), io::Error> {
+	match format {
+		OutputFormat::Text => {
+			writeln!(out, "Index of coincidence: {:.6}", result)?;
+		},
+		OutputFormat::Json => {
+			write!(out, "{{\"index_of_coincidence\":")?;
+			write_json_float(result, out)?;
+			writeln!(out, "}}")?;
+		},
+		OutputFormat::Csv => {
+			writeln!(out, "index_of_coincidence\n{}", result)?;
+		},
+	};
+	return Ok(());
+}
+
+#[cfg(test)]
+mod tests {
+	use std::io;
+	use crate::analytics::index_of_coincidence::index_of_coincidence;
+	use crate::analytics::index_of_coincidence::print_index_of_coincidence_result;
+	use std::io::Write;
+	use crate::output::OutputFormat;
+
+	#[test]
+	fn index_of_coincidence_errors() {
+		for vec in [vec![], vec![42]] {
+			let err = index_of_coincidence::<u8>(&vec).unwrap_err();
+			assert_eq!(err.kind(), io::ErrorKind::Other);
+			assert_eq!(
+				err.to_string(),
+				"Cannot calculate index of coincidence on less than 2 words");
+		}
+	}
+
+	#[test]
+	fn index_of_coincidence_u8() -> Result<(), io::Error> {
+		// 3 * 2 + 2 * 1 + 2 * 1 = 10, 9 * 8 = 72
+		let vec_u8: Vec<u8> = vec![223, 3, 17, 223, 255, 42, 3, 17, 223];
+		assert_eq!(index_of_coincidence(&vec_u8)?, 10.0 / 72.0);
+		return Ok(());
+	}
+
+	#[test]
+	fn index_of_coincidence_u32() -> Result<(), io::Error> {
+		let equal: Vec<u32> = vec![684631, 684631, 684631];
+		assert_eq!(index_of_coincidence(&equal)?, 1.0);
+		let distinct: Vec<u32> = vec![1, 2, 3, 4];
+		assert_eq!(index_of_coincidence(&distinct)?, 0.0);
+		return Ok(());
+	}
+
+	#[test]
+	fn print_index_of_coincidence_result_test() {
+		let mut out = Vec::new();
+		print_index_of_coincidence_result(10.0 / 72.0, &OutputFormat::Text, &mut out).unwrap();
+		let mut expected = Vec::new();
+		writeln!(expected, "Index of coincidence: 0.138889").unwrap();
+		assert_eq!(out, expected);
+	}
+
+	#[test]
+	fn print_index_of_coincidence_result_json() {
+		let mut out = Vec::new();
+		print_index_of_coincidence_result(0.125, &OutputFormat::Json, &mut out).unwrap();
+		let mut expected = Vec::new();
+		writeln!(expected, "{{\"index_of_coincidence\":0.125}}").unwrap();
+		assert_eq!(out, expected);
+	}
+
+	#[test]
+	fn print_index_of_coincidence_result_csv() {
+		let mut out = Vec::new();
+		print_index_of_coincidence_result(0.125, &OutputFormat::Csv, &mut out).unwrap();
+		let mut expected = Vec::new();
+		writeln!(expected, "index_of_coincidence\n0.125").unwrap();
+		assert_eq!(out, expected);
+	}
+}
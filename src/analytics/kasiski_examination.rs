@@ -1,169 +1,446 @@
+use std::collections::BTreeSet;
 use std::collections::HashMap;
-use std::ops::AddAssign;
 use std::hash::Hash;
-use std::collections::HashSet;
-use std::option::Option;
 use std::fmt::Display;
 use std::io::Write;
 use std::io;
+use crate::output::OutputFormat;
+use crate::output::write_text_list;
+use crate::output::write_json_array;
+use crate::output::write_csv_list;
 
-fn find_common_length<T: Eq>(vec: &Vec<T>, start1: usize, start2: usize) -> usize {
-	let mut common_length: usize = 0;
-	let mut pos1 = start1;
-	let mut pos2 = start2;
-	let vec_len = vec.len();
-	while pos1 < vec_len && pos2 < vec_len {
-		if vec[pos1] != vec[pos2] {
-			break;
+// Stable counting sort of the suffixes in order by their rank
+fn sort_by_rank(order: &Vec<usize>, rank: &Vec<usize>, classes: usize, sa: &mut Vec<usize>) {
+	let mut starts: Vec<usize> = vec![0; classes + 1];
+	for r in rank {
+		starts[*r + 1] += 1;
+	}
+	for c in 0..classes {
+		starts[c + 1] += starts[c];
+	}
+	for i in order {
+		sa[starts[rank[*i]]] = *i;
+		starts[rank[*i]] += 1;
+	}
+}
+
+// Suffix array by prefix doubling with counting sorts. Words are ranked in
+// order of their first occurence, which is enough to group equal prefixes.
+fn suffix_array<T: Copy + Eq + Hash>(vec: &[T]) -> Vec<usize> {
+	let n = vec.len();
+	let mut ids: HashMap<T, usize> = HashMap::new();
+	let mut rank: Vec<usize> = Vec::with_capacity(n);
+	for w in vec {
+		let next = ids.len();
+		rank.push(*ids.entry(*w).or_insert(next));
+	}
+	let mut classes = ids.len();
+	let mut sa: Vec<usize> = vec![0; n];
+	let mut order: Vec<usize> = (0..n).collect();
+	sort_by_rank(&order, &rank, classes, &mut sa);
+	let mut new_rank: Vec<usize> = vec![0; n];
+	let mut k = 1;
+	while classes < n {
+		// Sorted by the second half, suffixes without one come first
+		order.clear();
+		order.extend(n.saturating_sub(k)..n);
+		order.extend(sa.iter().filter(|i| **i >= k).map(|i| i - k));
+		sort_by_rank(&order, &rank, classes, &mut sa);
+		let second_rank = |i: usize| if i + k < n { Some(rank[i + k]) } else { None };
+		classes = 1;
+		new_rank[sa[0]] = 0;
+		for i in 1..n {
+			let (a, b) = (sa[i - 1], sa[i]);
+			if rank[a] != rank[b] || second_rank(a) != second_rank(b) {
+				classes += 1;
+			}
+			new_rank[b] = classes - 1;
 		}
-		common_length += 1;
-		pos1 += 1;
-		pos2 += 1;
+		std::mem::swap(&mut rank, &mut new_rank);
+		k *= 2;
 	}
-	return common_length;
+	return sa;
 }
 
-fn param_to_word<T: Copy>(vec: &Vec<T>, start: usize, length: usize) -> Vec<T> {
-	let mut result: Vec<T> = Vec::with_capacity(length);
-	for j in start..start + length {
-		result.push(vec[j]);
+// Kasai's algorithm, lcp[r] is the common prefix length of the suffixes
+// sa[r - 1] and sa[r], lcp[0] is 0
+fn lcp_array<T: Eq>(vec: &[T], sa: &Vec<usize>) -> Vec<usize> {
+	let n = vec.len();
+	let mut rank: Vec<usize> = vec![0; n];
+	for (r, i) in sa.iter().enumerate() {
+		rank[*i] = r;
 	}
-	return result;
+	let mut lcp: Vec<usize> = vec![0; n];
+	let mut h: usize = 0;
+	for i in 0..n {
+		if rank[i] == 0 {
+			h = 0;
+			continue;
+		}
+		let j = sa[rank[i] - 1];
+		while i + h < n && j + h < n && vec[i + h] == vec[j + h] {
+			h += 1;
+		}
+		lcp[rank[i]] = h;
+		h = h.saturating_sub(1);
+	}
+	return lcp;
 }
 
 
-// Iterator to iterate over Pair<T, T> from Vec<T>
-// Process every element value pair (e.g. (2, 7), (7, 2)) only once
-// do not iterate over pair where pair.0 == pair.1
-struct DeDupPairIter<'a, T: Hash> {
-	iter1: std::slice::Iter<'a, T>,
-	iter2: std::slice::Iter<'a, T>,
-	vec: &'a Vec<T>,
-	last1: Option<&'a T>,
-	processed: HashSet<(&'a T, &'a T)>,
+/// Repeated word, its starts are the entries start..end of the suffix array
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct KasiskiRepeat {
+	pub length: usize,
+	/// Smallest start of the word
+	pub first: usize,
+	start: usize,
+	end: usize,
 }
 
-impl<'a, T: Hash> DeDupPairIter<'a, T> {
-	pub fn new(vec: &'a Vec<T>) -> DeDupPairIter<'a, T>{
-		let mut iter1 = vec.into_iter();
-		let last1 = iter1.next();
-		return DeDupPairIter {
-			iter1: iter1,
-			iter2: vec.into_iter(),
-			vec: vec,
-			last1: last1,
-			processed: HashSet::new(),
-		};
+/// Maximal repeats of the input. The words and their starts are not copied,
+/// they are read from the input and its suffix array when they are needed.
+pub struct KasiskiWords<'a, T> {
+	vec: &'a [T],
+	suffix_array: Vec<usize>,
+	/// Longest words first, words of the same length by their first start
+	pub repeats: Vec<KasiskiRepeat>,
+}
+
+impl<'a, T> KasiskiWords<'a, T> {
+	pub fn word(&self, repeat: &KasiskiRepeat) -> &'a [T] {
+		return &self.vec[repeat.first..repeat.first + repeat.length];
+	}
+
+	/// Starts of the word in ascending order
+	pub fn starts(&self, repeat: &KasiskiRepeat) -> Vec<usize> {
+		let mut result = self.suffix_array[repeat.start..repeat.end].to_vec();
+		result.sort();
+		return result;
+	}
+
+	pub fn len(&self) -> usize {
+		return self.repeats.len();
+	}
+
+	pub fn is_empty(&self) -> bool {
+		return self.repeats.is_empty();
 	}
 }
 
-impl<'a, T: 'a + Eq + Hash> Iterator for DeDupPairIter<'a, T> {
-	type Item = (&'a T, &'a T);
-
-	fn next(&mut self) -> Option<Self::Item> {
-		loop {
-			let v1 = match self.last1 {
-				None => {
-					return None;
-				},
-				Some(val1) => val1,
-			};
-			match self.iter2.next() {
-				None => {
-					self.last1 = self.iter1.next();
-					self.iter2 = self.vec.into_iter();
-				},
-				Some(v2) => {
-					let process =
-						v1 != v2 &&
-						!self.processed.contains(&(v1, v2)) &&
-						!self.processed.contains(&(v2, v1));
-					if process {
-						self.processed.insert((v1, v2));
-						return Some((v1, v2));
-					}
-				},
-			};
+// Words before the starts of an interval, the start of the input differs
+// from every word
+#[derive(PartialEq, Clone, Copy)]
+enum LeftWords<T> {
+	Empty,
+	Same(T),
+	Different,
+}
+
+// Lcp interval of the suffix array during the bottom up traversal
+struct Interval<T> {
+	length: usize,
+	start: usize,
+	starts: BTreeSet<usize>,
+	// Smallest distance between two starts
+	gap: usize,
+	// Upper bound of the largest distance of a start to its nearest other
+	// start, made exact whenever it is checked
+	isolation: usize,
+	left: LeftWords<T>,
+}
+
+impl<T: Copy + Eq> Interval<T> {
+	fn new(length: usize, start: usize) -> Interval<T> {
+		return Interval {
+			length: length,
+			start: start,
+			starts: BTreeSet::new(),
+			gap: usize::MAX,
+			isolation: 0,
+			left: LeftWords::Empty,
+		};
+	}
+
+	fn leaf(vec: &[T], rank: usize, position: usize) -> Interval<T> {
+		let mut result = Interval::new(vec.len() - position, rank);
+		result.starts.insert(position);
+		result.left = match position {
+			0 => LeftWords::Different,
+			p => LeftWords::Same(vec[p - 1]),
+		};
+		return result;
+	}
+
+	// Insert the starts of the smaller interval into the larger one, every
+	// start is moved at most log n times. Starts only get closer to their
+	// neighbours, so the isolation of the larger interval stays a bound.
+	fn merge(&mut self, mut other: Interval<T>) {
+		if other.starts.len() > self.starts.len() {
+			std::mem::swap(&mut self.starts, &mut other.starts);
+			std::mem::swap(&mut self.isolation, &mut other.isolation);
+		}
+		if self.starts.len() == 1 {
+			// The single start gets a neighbour
+			self.isolation = 0;
+		}
+		self.gap = std::cmp::min(self.gap, other.gap);
+		for s in other.starts {
+			let mut distance = usize::MAX;
+			if let Some(p) = self.starts.range(..s).next_back() {
+				distance = s - p;
+			}
+			if let Some(n) = self.starts.range(s..).next() {
+				distance = std::cmp::min(distance, n - s);
+			}
+			self.gap = std::cmp::min(self.gap, distance);
+			self.isolation = std::cmp::max(self.isolation, distance);
+			self.starts.insert(s);
+		}
+		self.left = match (self.left, other.left) {
+			(LeftWords::Empty, l) | (l, LeftWords::Empty) => l,
+			(LeftWords::Same(a), LeftWords::Same(b)) if a == b => LeftWords::Same(a),
+			_ => LeftWords::Different,
 		};
 	}
-}
 
+	// Whether a start does not overlap any other start of the word
+	fn has_isolated_start(&mut self) -> bool {
+		if self.gap >= self.length {
+			return true;
+		}
+		if self.isolation < self.length {
+			return false;
+		}
+		let starts: Vec<usize> = self.starts.iter().copied().collect();
+		self.isolation = 0;
+		for (i, s) in starts.iter().enumerate() {
+			let previous = if i > 0 { s - starts[i - 1] } else { usize::MAX };
+			let next = if i + 1 < starts.len() { starts[i + 1] - s } else { usize::MAX };
+			self.isolation = std::cmp::max(self.isolation, std::cmp::min(previous, next));
+		}
+		return self.isolation >= self.length;
+	}
+}
 
-// Return start of duplicate words with a min length
-// Shorter duplicates are returned since longer matches can be by accident in the text
+/// Return the starts of repeated words with a min length
+/// Shorter duplicates are returned since longer matches can be by accident in the text
+/// Only maximal repeats are returned, which can neither be extended to the
+/// left nor to the right at all of their starts, since the others have the
+/// same distances. Words whose every start overlaps another start, like the
+/// shorter words of a run of zeros, are skipped since a longer repeat covers
+/// their occurrences. The lcp intervals of a suffix array are walked bottom
+/// up, which takes O(n log² n) time and O(n) memory.
 pub fn kasiski_examination<
-	T: Copy + AddAssign + Eq + Hash
->(vec: &Vec<T>, min_length: usize) -> HashMap<Vec<T>, HashSet<usize>> {
-	// If this is too ram heavy, it can be rewritten by comparing the text with
-	// itself while shifting one text one char to the right
-	let mut words_start: HashMap<Vec<T>, Vec<usize>> = HashMap::new();
-	let mut result: HashMap<Vec<T>, HashSet<usize>> = HashMap::new();
+	T: Copy + Eq + Hash
+>(vec: &[T], min_length: usize) -> KasiskiWords<'_, T> {
+	let mut result = KasiskiWords { vec: vec, suffix_array: Vec::new(), repeats: Vec::new() };
 	if vec.len() < min_length + 1 {
 		return result;
 	}
-	for i in 0..vec.len() - min_length + 1 {
-		let word = param_to_word(vec, i, min_length);
-		words_start.entry(word).or_insert(Vec::new()).push(i);
-	}
-	for (_, word_starts) in &words_start {
-		// Only iterate once over every tuple of starts
-		for starts in DeDupPairIter::new(word_starts) {
-			let check1 = starts.0 + min_length;
-			let check2 = starts.1 + min_length;
-			let l = find_common_length(vec, check1, check2);
-			let total_length = min_length + l;
-			let l_word = param_to_word(vec, *starts.0, total_length);
-			let set = result.entry(l_word).or_insert(HashSet::new());
-			set.insert(*starts.0);
-			set.insert(*starts.1);
+	let sa = suffix_array(vec);
+	let lcp = lcp_array(vec, &sa);
+	// An interval of length l holds every start of the word of length l
+	let mut stack: Vec<Interval<T>> = vec![Interval::new(0, 0)];
+	for i in 1..vec.len() + 1 {
+		let current = if i < vec.len() { lcp[i] } else { 0 };
+		let mut pending = Interval::leaf(vec, i - 1, sa[i - 1]);
+		let mut start = i - 1;
+		// The root has length 0 and is never closed
+		while current < stack[stack.len() - 1].length {
+			let mut interval = stack.pop().expect("Root interval is on the stack");
+			interval.merge(pending);
+			if interval.length >= min_length && interval.left == LeftWords::Different &&
+					interval.has_isolated_start() {
+				result.repeats.push(KasiskiRepeat {
+					length: interval.length,
+					first: *interval.starts.iter().next().expect("Interval has starts"),
+					start: interval.start,
+					end: i,
+				});
+			}
+			start = interval.start;
+			pending = interval;
+		}
+		let last = stack.len() - 1;
+		if current > stack[last].length {
+			let mut interval = Interval::new(current, start);
+			interval.merge(pending);
+			stack.push(interval);
+		} else {
+			stack[last].merge(pending);
 		}
 	}
+	result.repeats.sort_by_key(|r| (std::cmp::Reverse(r.length), r.first));
+	result.suffix_array = sa;
 	return result;
 }
 
-fn print_inner_vec<
+/// Write the result of kasiski_examination in the given format, longest
+/// words first
+pub fn print_kasiski_examination_result<
 	T: Display, W: Write
->(vec: &Vec<T>, out: &mut W) -> Result<(), io::Error> {
-	let mut first = true;
-	for k in vec {
-		if first {
-			write!(out, "{}", k)?;
-			first = false;
-		} else {
-			write!(out, ", {}", k)?;
+>(words: &KasiskiWords<T>, format: &OutputFormat, mut out: &mut W)
+		-> Result<(), io::Error> {
+	match format {
+		OutputFormat::Text => {
+			writeln!(out, "Words: {}", words.len())?;
+			for r in words.repeats.iter() {
+				// Write Set
+				write!(out, "{{")?;
+				write_text_list(&words.starts(r), &mut out)?;
+				write!(out, "}}: [")?;
+				// Write Vec
+				write_text_list(words.word(r), &mut out)?;
+				writeln!(out, "]")?;
+			}
+		},
+		OutputFormat::Json => {
+			write!(out, "{{\"words\":[")?;
+			for (k, r) in words.repeats.iter().enumerate() {
+				if k > 0 {
+					write!(out, ",")?;
+				}
+				write!(out, "{{\"positions\":")?;
+				write_json_array(&words.starts(r), out)?;
+				write!(out, ",\"word\":")?;
+				write_json_array(words.word(r), out)?;
+				write!(out, "}}")?;
+			}
+			writeln!(out, "]}}")?;
+		},
+		OutputFormat::Csv => {
+			writeln!(out, "word,length,positions,distances")?;
+			for r in words.repeats.iter() {
+				let starts = words.starts(r);
+				write_csv_list(words.word(r), out)?;
+				write!(out, ",{},", r.length)?;
+				write_csv_list(&starts, out)?;
+				write!(out, ",")?;
+				write_csv_list(&starts_distances(&starts, r.length), out)?;
+				writeln!(out)?;
+			}
+		},
+	};
+	return Ok(());
+}
+
+// Distances between every pair of sorted starts of a word of the given
+// length, overlapping pairs are skipped
+fn starts_distances(sorted: &Vec<usize>, length: usize) -> Vec<usize> {
+	let mut result: Vec<usize> = Vec::new();
+	for (i, start1) in sorted.iter().enumerate() {
+		for start2 in &sorted[i + 1..] {
+			if *start2 - *start1 >= length {
+				result.push(*start2 - *start1);
+			}
 		}
 	}
-	return Ok(());
+	return result;
 }
 
-pub fn print_kasiski_examination_result<
-	T: Display, W: Write
->(map: &HashMap<Vec<T>, HashSet<usize>>, mut out: &mut W) -> Result<(), io::Error> {
-	writeln!(out, "Words: {}", map.len())?;
-	let mut vec: Vec<(&Vec<T>, &HashSet<usize>)> = map.iter().collect();
-	vec.sort_by(|a, b| b.0.len().cmp(&a.0.len()));
-	for (i, j) in vec {
-		// Write Set
-		let mut starts: Vec<&usize> = j.iter().collect();
-		starts.sort_by(|a, b| a.cmp(b));
-		write!(out, "{{")?;
-		print_inner_vec(&starts, &mut out)?;
-		write!(out, "}}: [")?;
-		// Write Vec
-		print_inner_vec(&i, &mut out)?;
-		writeln!(out, "]")?;
+// Distances between every pair of starts of every repeated word
+fn kasiski_distances<T>(words: &KasiskiWords<T>) -> Vec<usize> {
+	let mut result: Vec<usize> = Vec::new();
+	for r in words.repeats.iter() {
+		result.append(&mut starts_distances(&words.starts(r), r.length));
+	}
+	return result;
+}
+
+fn divisors(value: usize) -> Vec<usize> {
+	let mut small: Vec<usize> = Vec::new();
+	let mut large: Vec<usize> = Vec::new();
+	let mut i = 1;
+	while i * i <= value {
+		if value % i == 0 {
+			small.push(i);
+			if i * i != value {
+				large.push(value / i);
+			}
+		}
+		i += 1;
+	}
+	large.reverse();
+	small.append(&mut large);
+	return small;
+}
+
+/// Possible key length with the number of distances it divides
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct PeriodCandidate {
+	pub period: usize,
+	pub votes: usize,
+}
+
+// Every distance votes for each of its divisors > 1
+fn distance_votes(distances: Vec<usize>) -> Vec<PeriodCandidate> {
+	let mut votes: HashMap<usize, usize> = HashMap::new();
+	for distance in distances {
+		for period in divisors(distance) {
+			if period > 1 {
+				*votes.entry(period).or_insert(0) += 1;
+			}
+		}
 	}
+	let mut result: Vec<PeriodCandidate> = votes.into_iter().map(
+		|(p, v)| PeriodCandidate { period: p, votes: v }).collect();
+	result.sort_by(|a, b| b.votes.cmp(&a.votes).then(a.period.cmp(&b.period)));
+	return result;
+}
+
+/// Every distance votes for each of its divisors > 1 as possible key length,
+/// most voted periods first
+pub fn kasiski_period_candidates<T>(words: &KasiskiWords<T>) -> Vec<PeriodCandidate> {
+	return distance_votes(kasiski_distances(words));
+}
+
+/// Write the result of kasiski_period_candidates in the given format
+pub fn print_kasiski_period_candidates<
+	W: Write
+>(candidates: &Vec<PeriodCandidate>, format: &OutputFormat, out: &mut W)
+		-> Result<(), io::Error> {
+	match format {
+		OutputFormat::Text => {
+			writeln!(out, "Period candidates: {}", candidates.len())?;
+			for c in candidates {
+				writeln!(out, "{}: {}", c.period, c.votes)?;
+			}
+		},
+		OutputFormat::Json => {
+			write!(out, "{{\"period_candidates\":[")?;
+			for (k, c) in candidates.iter().enumerate() {
+				if k > 0 {
+					write!(out, ",")?;
+				}
+				write!(out, "{{\"period\":{},\"votes\":{}}}", c.period, c.votes)?;
+			}
+			writeln!(out, "]}}")?;
+		},
+		OutputFormat::Csv => {
+			writeln!(out, "period,votes")?;
+			for c in candidates {
+				writeln!(out, "{},{}", c.period, c.votes)?;
+			}
+		},
+	};
 	return Ok(());
 }
 
+/// Write the total length of all repeated words in the given format
 pub fn print_kasiski_examination_total<
 	T: Display, W: Write
->(map: &HashMap<Vec<T>, HashSet<usize>>, out: &mut W) -> Result<(), io::Error> {
+>(words: &KasiskiWords<T>, format: &OutputFormat, out: &mut W)
+		-> Result<(), io::Error> {
 	let mut total = 0;
-	for (i, j) in map {
-		total += i.len() * j.len();
+	for r in words.repeats.iter() {
+		total += r.length * (r.end - r.start);
 	}
-	writeln!(out, "Total: {}", total)?;
+	match format {
+		OutputFormat::Text => writeln!(out, "Total: {}", total)?,
+		OutputFormat::Json => writeln!(out, "{{\"total\":{}}}", total)?,
+		OutputFormat::Csv => writeln!(out, "total\n{}", total)?,
+	};
 	return Ok(());
 }
 
@@ -172,13 +449,20 @@ mod tests {
 	use super::kasiski_examination;
 	use super::print_kasiski_examination_result;
 	use super::print_kasiski_examination_total;
-	use super::print_inner_vec;
-	use super::param_to_word;
-	use super::find_common_length;
-	use super::DeDupPairIter;
+	use super::print_kasiski_period_candidates;
+	use super::kasiski_period_candidates;
+	use super::KasiskiWords;
+	use super::PeriodCandidate;
+	use super::kasiski_distances;
+	use super::distance_votes;
+	use super::divisors;
+	use super::suffix_array;
+	use super::lcp_array;
 	use std::collections::HashMap;
 	use std::collections::HashSet;
+	use std::hash::Hash;
 	use std::io::Write;
+	use crate::output::OutputFormat;
 
 	macro_rules! set {
 		($($x:tt)*) => {
@@ -189,57 +473,76 @@ mod tests {
 		}
 	}
 
-	#[test]
-	fn param_to_word_u8() {
-		let vec_u8: Vec<u8> = vec![223, 3, 17, 223, 255, 42, 3, 17, 223];
-		assert_eq!(param_to_word(&vec_u8, 2, 3), vec![17, 223, 255]);
+	fn to_map<T: Copy + Eq + Hash>(words: &KasiskiWords<T>) -> HashMap<Vec<T>, HashSet<usize>> {
+		return words.repeats.iter().map(
+			|r| (words.word(r).to_vec(), words.starts(r).into_iter().collect())).collect();
 	}
 
+	// Repeats [3, 17, 223, 4, 2] at 1 and 8 and [17, 223] at 2, 6 and 9
+	const WORDS: [u16; 13] = [8, 3, 17, 223, 4, 2, 17, 223, 3, 17, 223, 4, 2];
+
 	#[test]
-	fn param_to_word_u32() {
-		let vec_u32: Vec<u32> = vec![50, 12, 4, 136];
-		assert_eq!(param_to_word(&vec_u32, 2, 2), vec![4, 136]);
+	fn suffix_array_first_occurence_order() {
+		// Words are ordered b < a < n
+		let vec = b"banana".to_vec();
+		let sa = suffix_array(&vec);
+		assert_eq!(sa, vec![0, 5, 3, 1, 4, 2]);
+		assert_eq!(lcp_array(&vec, &sa), vec![0, 0, 1, 3, 0, 2]);
 	}
 
 	#[test]
-	fn find_common_length_u8() {
-		let vec_u8: Vec<u8> = vec![223, 3, 17, 223, 223, 3, 17, 223];
-		assert_eq!(find_common_length(&vec_u8, 0, 4), 4);
-		assert_eq!(find_common_length(&vec_u8, 1, 5), 3);
-		assert_eq!(find_common_length(&vec_u8, 2, 6), 2);
-		assert_eq!(find_common_length(&vec_u8, 0, 3), 1);
-		assert_eq!(find_common_length(&vec_u8, 0, 2), 0);
+	fn suffix_array_repeated_word() {
+		let vec: Vec<u64> = vec![7, 7, 7, 7];
+		let sa = suffix_array(&vec);
+		assert_eq!(sa, vec![3, 2, 1, 0]);
+		assert_eq!(lcp_array(&vec, &sa), vec![0, 1, 2, 3]);
+		assert_eq!(suffix_array::<u8>(&[]), vec![]);
+		assert_eq!(suffix_array(&[9_u8]), vec![0]);
 	}
 
 	#[test]
-	fn find_common_length_u64() {
-		let vec_u64: Vec<u64> = vec![685654, 54433, 76856, 765835, 24523];
-		assert_eq!(find_common_length(&vec_u64, 1, 1), 4);
+	fn kasiski_examination_overlapping_repeats() {
+		// Every word of a run overlaps its next start
+		let vec: Vec<u8> = vec![7, 7, 7, 7];
+		assert_eq!(kasiski_examination(&vec, 2).repeats, vec![]);
+		let vec: Vec<u8> = vec![7, 7, 1, 7, 7];
+		assert_eq!(to_map(&kasiski_examination(&vec, 2)), [(vec![7, 7], set![0, 3])].iter().cloned().collect());
+		// The overlapping starts 1 and 3 are kept, only their distance is dropped
+		let mut vec: Vec<u8> = vec![1, 5, 6, 5, 6, 5, 2];
+		vec.extend(10..30);
+		vec.extend([3, 5, 6, 5, 4]);
+		let words = kasiski_examination(&vec, 3);
+		assert_eq!(to_map(&words), [(vec![5, 6, 5], set![1, 3, 28])].iter().cloned().collect());
+		assert_eq!(kasiski_distances(&words), vec![27, 25]);
 	}
 
 	#[test]
-	fn dedup_pair_iter() {
-		let vec: Vec<usize> = vec![8, 2, 7, 2, 3, 7];
-		let mut result = Vec::new();
-		for i in DeDupPairIter::new(&vec) {
-			result.push((*i.0, *i.1));
-		}
-		let exp_res: Vec<(usize, usize)> = vec![
-			(8, 2), (8, 7), (8, 3), (2, 7), (2, 3), (7, 3)];
-		assert_eq!(result, exp_res);
+	fn kasiski_examination_zero_runs() {
+		// The old search returned every word of the runs with all of its
+		// starts, about n² / 2 starts
+		let mut vec: Vec<u8> = vec![0; 20000];
+		vec.push(1);
+		vec.extend(vec![0; 20000]);
+		let words = kasiski_examination(&vec, 5);
+		assert_eq!(words.len(), 1);
+		assert_eq!(words.repeats[0].length, 20000);
+		assert_eq!(words.starts(&words.repeats[0]), vec![0, 20001]);
+		let mut out = Vec::new();
+		print_kasiski_examination_result(&words, &OutputFormat::Text, &mut out).unwrap();
+		assert!(out.len() < 3 * vec.len());
 	}
 
 	#[test]
 	fn kasiski_examination_u8() {
 		let vec_u8: Vec<u8> = vec![223, 3, 17, 223, 255, 223, 255, 42, 3, 17, 223];
-		assert_eq!(kasiski_examination(&vec_u8, 4), HashMap::new());
+		assert_eq!(to_map(&kasiski_examination(&vec_u8, 4)), HashMap::new());
 		assert_eq!(
-			kasiski_examination(&vec_u8, 3),
+			to_map(&kasiski_examination(&vec_u8, 3)),
 			[(vec![3, 17, 223], set![1, 8])].iter().cloned().collect());
+		// [17, 223] is always preceded by 3
 		assert_eq!(
-			kasiski_examination(&vec_u8, 2),
+			to_map(&kasiski_examination(&vec_u8, 2)),
 			[(vec![3, 17, 223], set![1, 8]),
-			 (vec![17, 223], set![2, 9]),
 			 (vec![223, 255], set![3, 5]),
 			].iter().cloned().collect());
 	}
@@ -255,93 +558,172 @@ mod tests {
 			5846, 5274, 23524, 54824, // w3
 		];
 		let mut result: HashMap<Vec<u16>, HashSet<usize>> = HashMap::new();
-		assert_eq!(kasiski_examination(&vec, 8), result);
+		assert_eq!(to_map(&kasiski_examination(&vec, 8)), result);
 		result.insert(vec![5846, 5274, 23524, 54824, 45754, 3563], set![2, 16]);
 		result.insert(vec![46745, 6565, 34534, 7856, 57456, 56751], set![10, 22]);
-		assert_eq!(kasiski_examination(&vec, 6), result);
-		result.insert(vec![5274, 23524, 54824, 45754, 3563], set![3, 17]);
-		result.insert(vec![6565, 34534, 7856, 57456, 56751], set![11, 23]);
-		assert_eq!(kasiski_examination(&vec, 5), result);
-		result.insert(vec![23524, 54824, 45754, 3563], set![4, 18]);
-		result.insert(vec![34534, 7856, 57456, 56751], set![12, 24]);
+		assert_eq!(to_map(&kasiski_examination(&vec, 6)), result);
+		assert_eq!(to_map(&kasiski_examination(&vec, 5)), result);
 		result.insert(vec![5846, 5274, 23524, 54824], set![2, 16, 29]);
-		assert_eq!(kasiski_examination(&vec, 4), result);
+		assert_eq!(to_map(&kasiski_examination(&vec, 4)), result);
 	}
 
 	#[test]
 	fn kasiski_examination_empty() {
 		let vec: Vec<u128> = vec![];
-		assert_eq!(kasiski_examination(&vec, 8), HashMap::new());
+		assert!(kasiski_examination(&vec, 8).is_empty());
 	}
 
 	#[test]
-	fn print_inner_vec_empty() {
+	fn kasiski_examination_order() {
+		let words = kasiski_examination(&WORDS, 2);
+		let repeats: Vec<(usize, usize)> = words.repeats.iter().map(|r| (r.length, r.first)).collect();
+		assert_eq!(repeats, vec![(5, 1), (2, 2)]);
+		assert_eq!(words.word(&words.repeats[0]), [3, 17, 223, 4, 2]);
+		assert_eq!(words.starts(&words.repeats[1]), vec![2, 6, 9]);
+	}
+
+	#[test]
+	fn print_kasiski_examination_result_empty() {
 		let mut out = Vec::new();
-		let vec: Vec<u8> = Vec::new();
-		print_inner_vec(&vec, &mut out).unwrap();
-		assert_eq!(std::str::from_utf8(&out), Ok(""));
+		let vec: Vec<u16> = vec![1, 2, 3];
+		print_kasiski_examination_result(&kasiski_examination(&vec, 2), &OutputFormat::Text, &mut out).unwrap();
+		let mut expected = Vec::new();
+		writeln!(expected, "Words: 0").unwrap();
+		assert_eq!(out, expected);
 	}
 
 	#[test]
-	fn print_inner_vec_one_element() {
+	fn print_kasiski_examination_result_words() {
 		let mut out = Vec::new();
-		print_inner_vec(&vec![24 as u8], &mut out).unwrap();
-		assert_eq!(std::str::from_utf8(&out), Ok("24"));
+		print_kasiski_examination_result(&kasiski_examination(&WORDS, 2), &OutputFormat::Text, &mut out).unwrap();
+		let mut expected = Vec::new();
+		writeln!(expected, "Words: 2").unwrap();
+		writeln!(expected, "{{1, 8}}: [3, 17, 223, 4, 2]").unwrap();
+		writeln!(expected, "{{2, 6, 9}}: [17, 223]").unwrap();
+		assert_eq!(out, expected);
 	}
 
 	#[test]
-	fn print_inner_vec_multiple_elements() {
+	fn print_kasiski_examination_total_empty() {
 		let mut out = Vec::new();
-		print_inner_vec(&vec![24 as u8, 5, 32, 7], &mut out).unwrap();
-		assert_eq!(std::str::from_utf8(&out), Ok("24, 5, 32, 7"));
+		let vec: Vec<u16> = vec![];
+		print_kasiski_examination_total(&kasiski_examination(&vec, 2), &OutputFormat::Text, &mut out).unwrap();
+		let mut expected = Vec::new();
+		writeln!(expected, "Total: 0").unwrap();
+		assert_eq!(out, expected);
 	}
 
 	#[test]
-	fn print_kasiski_examination_result_empty() {
+	fn print_kasiski_examination_total_words() {
 		let mut out = Vec::new();
-		let map: HashMap<Vec<u16>, HashSet<usize>> = HashMap::new();
-		print_kasiski_examination_result(&map, &mut out).unwrap();
+		print_kasiski_examination_total(&kasiski_examination(&WORDS, 2), &OutputFormat::Text, &mut out).unwrap();
 		let mut expected = Vec::new();
-		writeln!(expected, "Words: 0").unwrap();
+		writeln!(expected, "Total: 16").unwrap();
 		assert_eq!(out, expected);
 	}
 
+	fn to_candidates(vec: Vec<(usize, usize)>) -> Vec<PeriodCandidate> {
+		return vec.iter().map(
+			|(p, v)| PeriodCandidate { period: *p, votes: *v }).collect();
+	}
+
 	#[test]
-	fn print_kasiski_examination_result_words() {
+	fn divisors_test() {
+		assert_eq!(divisors(1), vec![1]);
+		assert_eq!(divisors(7), vec![1, 7]);
+		assert_eq!(divisors(12), vec![1, 2, 3, 4, 6, 12]);
+		assert_eq!(divisors(16), vec![1, 2, 4, 8, 16]);
+	}
+
+	#[test]
+	fn kasiski_distances_test() {
+		let mut result = kasiski_distances(&kasiski_examination(&WORDS, 2));
+		result.sort();
+		assert_eq!(result, vec![3, 4, 7, 7]);
+	}
+
+	#[test]
+	fn kasiski_period_candidates_test() {
+		assert_eq!(
+			distance_votes(vec![12, 8, 18, 10]),
+			to_candidates(vec![(2, 4), (3, 2), (4, 2), (6, 2), (5, 1), (8, 1),
+			                   (9, 1), (10, 1), (12, 1), (18, 1)]));
+		assert_eq!(
+			kasiski_period_candidates(&kasiski_examination(&WORDS, 2)),
+			to_candidates(vec![(7, 2), (2, 1), (3, 1), (4, 1)]));
+	}
+
+	#[test]
+	fn kasiski_period_candidates_empty() {
+		let vec: Vec<u16> = vec![];
+		assert_eq!(kasiski_period_candidates(&kasiski_examination(&vec, 2)), vec![]);
+	}
+
+	#[test]
+	fn print_kasiski_period_candidates_test() {
 		let mut out = Vec::new();
-		let map: HashMap<Vec<u16>, HashSet<usize>> = [
-			(vec![17, 223], set![2, 6, 9]),
-			(vec![3, 17, 223, 4, 2], set![1, 8]),
-			(vec![223, 255, 4], set![1, 2, 3, 5])].iter().cloned().collect();
-		print_kasiski_examination_result(&map, &mut out).unwrap();
+		print_kasiski_period_candidates(
+			&to_candidates(vec![(3, 5), (6, 2)]),
+			&OutputFormat::Text, &mut out).unwrap();
 		let mut expected = Vec::new();
-		writeln!(expected, "Words: 3").unwrap();
-		writeln!(expected, "{{1, 8}}: [3, 17, 223, 4, 2]").unwrap();
-		writeln!(expected, "{{1, 2, 3, 5}}: [223, 255, 4]").unwrap();
-		writeln!(expected, "{{2, 6, 9}}: [17, 223]").unwrap();
+		writeln!(expected, "Period candidates: 2\n3: 5\n6: 2").unwrap();
 		assert_eq!(out, expected);
 	}
 
 	#[test]
-	fn print_kasiski_examination_total_empty() {
+	fn print_kasiski_examination_result_json() {
 		let mut out = Vec::new();
-		let map: HashMap<Vec<u16>, HashSet<usize>> = HashMap::new();
-		print_kasiski_examination_total(&map, &mut out).unwrap();
+		print_kasiski_examination_result(&kasiski_examination(&WORDS, 2), &OutputFormat::Json, &mut out).unwrap();
 		let mut expected = Vec::new();
-		writeln!(expected, "Total: 0").unwrap();
+		writeln!(
+			expected,
+			"{{\"words\":[{{\"positions\":[1,8],\"word\":[3,17,223,4,2]}},\
+			{{\"positions\":[2,6,9],\"word\":[17,223]}}]}}").unwrap();
 		assert_eq!(out, expected);
 	}
 
 	#[test]
-	fn print_kasiski_examination_total_words() {
+	fn print_kasiski_examination_total_json() {
+		let mut out = Vec::new();
+		print_kasiski_examination_total(&kasiski_examination(&WORDS, 2), &OutputFormat::Json, &mut out).unwrap();
+		let mut expected = Vec::new();
+		writeln!(expected, "{{\"total\":16}}").unwrap();
+		assert_eq!(out, expected);
+	}
+
+	#[test]
+	fn print_kasiski_period_candidates_json() {
+		let mut out = Vec::new();
+		print_kasiski_period_candidates(
+			&to_candidates(vec![(3, 5), (6, 2)]),
+			&OutputFormat::Json, &mut out).unwrap();
+		let mut expected = Vec::new();
+		writeln!(
+			expected,
+			"{{\"period_candidates\":[{{\"period\":3,\"votes\":5}},\
+			{{\"period\":6,\"votes\":2}}]}}").unwrap();
+		assert_eq!(out, expected);
+	}
+
+	#[test]
+	fn print_kasiski_examination_result_csv() {
+		let mut out = Vec::new();
+		print_kasiski_examination_result(&kasiski_examination(&WORDS, 2), &OutputFormat::Csv, &mut out).unwrap();
+		let mut expected = Vec::new();
+		writeln!(expected, "word,length,positions,distances").unwrap();
+		writeln!(expected, "3 17 223 4 2,5,1 8,7").unwrap();
+		writeln!(expected, "17 223,2,2 6 9,4 7 3").unwrap();
+		assert_eq!(out, expected);
+	}
+
+	#[test]
+	fn print_kasiski_period_candidates_csv() {
 		let mut out = Vec::new();
-		let map: HashMap<Vec<u16>, HashSet<usize>> = [
-			(vec![17, 223], set![2, 6, 9]),
-			(vec![3, 17, 223, 4, 2], set![1, 8]),
-			(vec![223, 255, 4], set![1, 2, 3, 5])].iter().cloned().collect();
-		print_kasiski_examination_total(&map, &mut out).unwrap();
+		print_kasiski_period_candidates(
+			&to_candidates(vec![(3, 5), (6, 2)]),
+			&OutputFormat::Csv, &mut out).unwrap();
 		let mut expected = Vec::new();
-		writeln!(expected, "Total: 28").unwrap();
+		writeln!(expected, "period,votes\n3,5\n6,2").unwrap();
 		assert_eq!(out, expected);
 	}
 }
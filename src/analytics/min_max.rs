@@ -2,11 +2,19 @@ use std::io;
 use std::cmp::PartialOrd;
 use std::fmt::Display;
 use std::io::Write;
+use crate::output::OutputFormat;
 
-pub fn min_max<T: Copy + PartialOrd>(vec: &Vec<T>) -> Result<(T, T), io::Error> {
-	if vec.len() == 0 {
-		return Err(io::Error::new(io::ErrorKind::Other,
-		                          "Cannot calculate min/max on empty input"));
+/// Smallest and largest word of an input
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct MinMax<T> {
+	pub min: T,
+	pub max: T,
+}
+
+/// Find the smallest and largest word, fails on empty input
+pub fn min_max<T: Copy + PartialOrd>(vec: &[T]) -> Result<MinMax<T>, io::Error> {
+	if vec.is_empty() {
+		return Err(io::Error::other("Cannot calculate min/max on empty input"));
 	}
 	let mut min: T = vec[0];
 	let mut max: T = vec[0];
@@ -18,13 +26,49 @@ pub fn min_max<T: Copy + PartialOrd>(vec: &Vec<T>) -> Result<(T, T), io::Error>
 			max = *i;
 		}
 	}
-	return Ok((min, max));
+	return Ok(MinMax { min: min, max: max });
+}
+
+/// Find the smallest and largest word of all chunks, fails on empty input
+pub fn min_max_chunks<
+	T: Copy + PartialOrd, I: Iterator<Item = Result<Vec<T>, io::Error>>
+>(chunks: I) -> Result<MinMax<T>, io::Error> {
+	let mut result: Option<MinMax<T>> = None;
+	for chunk in chunks {
+		let chunk = chunk?;
+		if chunk.is_empty() {
+			continue;
+		}
+		let m = min_max(&chunk)?;
+		result = match result {
+			None => Some(m),
+			Some(r) => Some(MinMax {
+				min: if m.min < r.min { m.min } else { r.min },
+				max: if m.max > r.max { m.max } else { r.max },
+			}),
+		};
+	}
+	return match result {
+		Some(r) => Ok(r),
+		None => Err(io::Error::other("Cannot calculate min/max on empty input")),
+	};
 }
 
+/// Write the result of min_max in the given format
 pub fn print_min_max_result<
 	T: Display, W: Write
->(result: &(T, T), out: &mut W) -> Result<(), io::Error> {
-	writeln!(out, "Minimum: {}, Maximum: {}", result.0, result.1)?;
+>(result: &MinMax<T>, format: &OutputFormat, out: &mut W) -> Result<(), io::Error> {
+	match format {
+		OutputFormat::Text => {
+			writeln!(out, "Minimum: {}, Maximum: {}", result.min, result.max)?;
+		},
+		OutputFormat::Json => {
+			writeln!(out, "{{\"minimum\":{},\"maximum\":{}}}", result.min, result.max)?;
+		},
+		OutputFormat::Csv => {
+			writeln!(out, "minimum,maximum\n{},{}", result.min, result.max)?;
+		},
+	};
 	return Ok(());
 }
 
@@ -32,8 +76,11 @@ pub fn print_min_max_result<
 mod tests {
 	use std::io;
 	use crate::analytics::min_max::min_max;
+	use crate::analytics::min_max::min_max_chunks;
+	use crate::analytics::min_max::MinMax;
 	use crate::analytics::min_max::print_min_max_result;
 	use std::io::Write;
+	use crate::output::OutputFormat;
 
 	fn check_min_max_error(some_err: Option<io::Error>) -> Result<(), ()> {
 		if let Some(err) = some_err {
@@ -61,33 +108,63 @@ mod tests {
 		return Ok(());
 	}
 
+	#[test]
+	fn min_max_chunks_u16() -> Result<(), io::Error> {
+		let chunks: Vec<Result<Vec<u16>, io::Error>> = vec![
+			Ok(vec![300, 17]), Ok(vec![]), Ok(vec![65000, 5, 400])];
+		assert_eq!(min_max_chunks(chunks.into_iter())?, MinMax { min: 5, max: 65000 });
+		check_min_max_error(min_max_chunks(Vec::<Result<Vec<u8>, io::Error>>::new().into_iter()).err())
+			.unwrap();
+		let failing = vec![Ok(vec![1_u8]), Err(io::Error::other("read failed"))];
+		assert_eq!(min_max_chunks(failing.into_iter()).unwrap_err().to_string(), "read failed");
+		return Ok(());
+	}
+
 	#[test]
 	fn min_max_u8() -> Result<(), io::Error> {
 		let vec_u8: Vec<u8> = vec![223, 3, 17, 25, 255, 42, 102];
-		assert_eq!(min_max(&vec_u8)?, (3, 255));
+		assert_eq!(min_max(&vec_u8)?, MinMax { min: 3, max: 255 });
 		return Ok(());
 	}
 
 	#[test]
 	fn min_max_u32() -> Result<(), io::Error> {
 		let vec_u32: Vec<u32> = vec![223, 3, 17, 25, 255, 42, 102];
-		assert_eq!(min_max(&vec_u32)?, (3, 255));
+		assert_eq!(min_max(&vec_u32)?, MinMax { min: 3, max: 255 });
 		return Ok(());
 	}
 
 	#[test]
 	fn min_max_u128() -> Result<(), io::Error> {
 		let vec_u128: Vec<u128> = vec![223, 3, 17, 25, 255, 42, 102];
-		assert_eq!(min_max(&vec_u128)?, (3, 255));
+		assert_eq!(min_max(&vec_u128)?, MinMax { min: 3, max: 255 });
 		return Ok(());
 	}
 
 	#[test]
 	fn print_min_max_result_test() {
 		let mut out = Vec::new();
-		print_min_max_result(&(7, 19), &mut out).unwrap();
+		print_min_max_result(&MinMax { min: 7, max: 19 }, &OutputFormat::Text, &mut out).unwrap();
 		let mut expected = Vec::new();
 		writeln!(expected, "Minimum: 7, Maximum: 19").unwrap();
 		assert_eq!(out, expected);
 	}
+
+	#[test]
+	fn print_min_max_result_json() {
+		let mut out = Vec::new();
+		print_min_max_result(&MinMax { min: 7, max: 19 }, &OutputFormat::Json, &mut out).unwrap();
+		let mut expected = Vec::new();
+		writeln!(expected, "{{\"minimum\":7,\"maximum\":19}}").unwrap();
+		assert_eq!(out, expected);
+	}
+
+	#[test]
+	fn print_min_max_result_csv() {
+		let mut out = Vec::new();
+		print_min_max_result(&MinMax { min: 7, max: 19 }, &OutputFormat::Csv, &mut out).unwrap();
+		let mut expected = Vec::new();
+		writeln!(expected, "minimum,maximum\n7,19").unwrap();
+		assert_eq!(out, expected);
+	}
 }
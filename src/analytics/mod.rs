@@ -1,12 +1,59 @@
+//! Analysis methods on a vector of words and the functions to write their
+//! results
+
 mod min_max;
+pub use min_max::MinMax;
 pub use min_max::min_max;
+pub use min_max::min_max_chunks;
 pub use min_max::print_min_max_result;
 
 mod frequency_analysis;
+pub use frequency_analysis::Frequencies;
+pub use frequency_analysis::FrequencyCounter;
 pub use frequency_analysis::frequency_analysis;
+pub use frequency_analysis::frequency_analysis_chunks;
 pub use frequency_analysis::print_frequency_analysis_result;
+pub use frequency_analysis::ngram_frequency_analysis;
+pub use frequency_analysis::print_ngram_frequency_analysis_result;
 
 mod kasiski_examination;
+pub use kasiski_examination::KasiskiRepeat;
+pub use kasiski_examination::KasiskiWords;
+pub use kasiski_examination::PeriodCandidate;
 pub use kasiski_examination::kasiski_examination;
 pub use kasiski_examination::print_kasiski_examination_result;
 pub use kasiski_examination::print_kasiski_examination_total;
+pub use kasiski_examination::kasiski_period_candidates;
+pub use kasiski_examination::print_kasiski_period_candidates;
+
+mod index_of_coincidence;
+pub use index_of_coincidence::frequencies_to_index_of_coincidence;
+pub use index_of_coincidence::index_of_coincidence;
+pub use index_of_coincidence::print_index_of_coincidence_result;
+
+mod period_sweep;
+pub use period_sweep::PeriodIndexOfCoincidence;
+pub use period_sweep::period_sweep;
+pub use period_sweep::friedman_test;
+pub use period_sweep::print_period_sweep_result;
+pub use period_sweep::print_friedman_test_result;
+
+mod entropy;
+pub use entropy::EntropyWindow;
+pub use entropy::frequencies_to_entropy;
+pub use entropy::entropy;
+pub use entropy::entropy_profile;
+pub use entropy::entropy_chunks;
+pub use entropy::entropy_profile_chunks;
+pub use entropy::print_entropy_result;
+pub use entropy::print_entropy_profile;
+
+mod chi_squared;
+pub use chi_squared::LanguageChiSquared;
+pub use chi_squared::is_printable;
+pub use chi_squared::frequencies_to_letter_counts;
+pub use chi_squared::letter_chi_squared;
+pub use chi_squared::probabilities_chi_squared;
+pub use chi_squared::text_chi_squared;
+pub use chi_squared::chi_squared;
+pub use chi_squared::print_chi_squared_result;
@@ -0,0 +1,231 @@
+use std::ops::AddAssign;
+use std::hash::Hash;
+use std::io::Write;
+use std::io;
+use crate::input::filter_input_vec;
+use super::index_of_coincidence::index_of_coincidence;
+use crate::output::OutputFormat;
+use crate::output::write_json_float;
+
+// Index of coincidence of english plaintext and uniformly random letters
+const PLAINTEXT_IC: f64 = 0.0667;
+const RANDOM_IC: f64 = 1.0 / 26.0;
+
+/// Average index of coincidence of the columns of one period
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct PeriodIndexOfCoincidence {
+	pub period: usize,
+	pub index_of_coincidence: f64,
+}
+
+/// Split the input into period columns and calculate the average index of
+/// coincidence of all columns for every period from 1 to max_period.
+/// Periods with columns shorter than 2 words are not calculated.
+pub fn period_sweep<
+	T: Copy + AddAssign + Eq + Hash
+>(vec: &[T], max_period: usize) -> Result<Vec<PeriodIndexOfCoincidence>, io::Error> {
+	let mut result: Vec<PeriodIndexOfCoincidence> = Vec::new();
+	for period in 1..max_period + 1 {
+		if period > 1 && vec.len() / period < 2 {
+			break;
+		}
+		let mut sum: f64 = 0.0;
+		for column in 0..period {
+			let column_vec = filter_input_vec(vec, period, column)?;
+			sum += index_of_coincidence(&column_vec)?;
+		}
+		result.push(PeriodIndexOfCoincidence {
+			period: period,
+			index_of_coincidence: sum / (period as f64),
+		});
+	}
+	return Ok(result);
+}
+
+/// Friedman's estimate of the key length from the index of coincidence of the
+/// whole input and its length, assuming english text. An index of coincidence
+/// too low for the length, as of short or random input, gives no estimate and
+/// one above the plaintext index of coincidence gives a key length of 1.
+pub fn friedman_test(ic: f64, len: usize) -> Option<f64> {
+	let n = len as f64;
+	let denominator = (n - 1.0) * ic - n * RANDOM_IC + PLAINTEXT_IC;
+	if denominator <= 0.0 {
+		return None;
+	}
+	return Some(f64::max((PLAINTEXT_IC - RANDOM_IC) * n / denominator, 1.0));
+}
+
+/// Write the result of period_sweep in the given format
+pub fn print_period_sweep_result<
+	W: Write
+>(result: &Vec<PeriodIndexOfCoincidence>, format: &OutputFormat, out: &mut W)
+		-> Result<(), io::Error> {
+	match format {
+		OutputFormat::Text => {
+			writeln!(out, "Periods: {}", result.len())?;
+			for p in result {
+				writeln!(out, "{}: {:.6}", p.period, p.index_of_coincidence)?;
+			}
+		},
+		OutputFormat::Json => {
+			write!(out, "{{\"periods\":[")?;
+			for (k, p) in result.iter().enumerate() {
+				if k > 0 {
+					write!(out, ",")?;
+				}
+				write!(out, "{{\"period\":{},\"index_of_coincidence\":", p.period)?;
+				write_json_float(p.index_of_coincidence, out)?;
+				write!(out, "}}")?;
+			}
+			writeln!(out, "]}}")?;
+		},
+		OutputFormat::Csv => {
+			writeln!(out, "period,index_of_coincidence")?;
+			for p in result {
+				writeln!(out, "{},{}", p.period, p.index_of_coincidence)?;
+			}
+		},
+	};
+	return Ok(());
+}
+
+/// Write the result of friedman_test in the given format
+pub fn print_friedman_test_result<
+	W: Write
+>(result: Option<f64>, format: &OutputFormat, out: &mut W) -> Result<(), io::Error> {
+	match (format, result) {
+		(OutputFormat::Text, Some(estimate)) => {
+			writeln!(out, "Friedman estimate: {:.2}", estimate)?;
+		},
+		(OutputFormat::Text, None) => {
+			writeln!(out, "Friedman estimate: none")?;
+		},
+		(OutputFormat::Json, _) => {
+			write!(out, "{{\"friedman_estimate\":")?;
+			write_json_float(result.unwrap_or(f64::NAN), out)?;
+			writeln!(out, "}}")?;
+		},
+		(OutputFormat::Csv, Some(estimate)) => {
+			writeln!(out, "friedman_estimate\n{}", estimate)?;
+		},
+		(OutputFormat::Csv, None) => {
+			writeln!(out, "friedman_estimate\n")?;
+		},
+	};
+	return Ok(());
+}
+
+#[cfg(test)]
+mod tests {
+	use std::io;
+	use crate::analytics::period_sweep::period_sweep;
+	use crate::analytics::period_sweep::PeriodIndexOfCoincidence;
+	use crate::analytics::period_sweep::friedman_test;
+	use crate::analytics::period_sweep::print_period_sweep_result;
+	use crate::analytics::period_sweep::print_friedman_test_result;
+	use std::io::Write;
+	use crate::output::OutputFormat;
+
+	fn to_periods(vec: Vec<(usize, f64)>) -> Vec<PeriodIndexOfCoincidence> {
+		return vec.iter().map(|(p, ic)| PeriodIndexOfCoincidence {
+			period: *p,
+			index_of_coincidence: *ic,
+		}).collect();
+	}
+
+	#[test]
+	fn period_sweep_u8() -> Result<(), io::Error> {
+		let vec_u8: Vec<u8> = vec![1, 2, 3, 1, 2, 3, 1, 2, 3];
+		assert_eq!(
+			period_sweep(&vec_u8, 5)?,
+			to_periods(vec![(1, 9.0 / 36.0), (2, (4.0 / 20.0 + 2.0 / 12.0) / 2.0),
+			                (3, 1.0), (4, 0.0)]));
+		return Ok(());
+	}
+
+	#[test]
+	fn period_sweep_u16() -> Result<(), io::Error> {
+		let vec_u16: Vec<u16> = vec![5846, 5274, 5846, 5274];
+		assert_eq!(
+			period_sweep(&vec_u16, 2)?,
+			to_periods(vec![(1, 2.0 / 6.0), (2, 1.0)]));
+		return Ok(());
+	}
+
+	#[test]
+	fn period_sweep_errors() {
+		let vec: Vec<u8> = vec![1];
+		let err = period_sweep(&vec, 3).unwrap_err();
+		assert_eq!(err.kind(), io::ErrorKind::Other);
+		assert_eq!(
+			err.to_string(),
+			"Cannot calculate index of coincidence on less than 2 words");
+	}
+
+	#[test]
+	fn friedman_test_estimate() {
+		assert!((friedman_test(0.0667, 1000).unwrap() - 1.0).abs() < 1e-9);
+		assert!(friedman_test(0.045, 1000).unwrap() > 4.0);
+		assert!(friedman_test(0.045, 1000).unwrap() < 4.5);
+		assert_eq!(friedman_test(0.08, 1000), Some(1.0));
+		// Below the random index of coincidence the denominator is negative
+		assert_eq!(friedman_test(0.02, 1000), None);
+		assert_eq!(friedman_test(0.0, 2), None);
+	}
+
+	#[test]
+	fn print_period_sweep_result_test() {
+		let mut out = Vec::new();
+		print_period_sweep_result(
+			&to_periods(vec![(1, 0.25), (2, 0.5)]),
+			&OutputFormat::Text, &mut out).unwrap();
+		let mut expected = Vec::new();
+		writeln!(expected, "Periods: 2\n1: 0.250000\n2: 0.500000").unwrap();
+		assert_eq!(out, expected);
+	}
+
+	#[test]
+	fn print_friedman_test_result_test() {
+		let mut out = Vec::new();
+		print_friedman_test_result(Some(4.30454), &OutputFormat::Text, &mut out).unwrap();
+		print_friedman_test_result(None, &OutputFormat::Text, &mut out).unwrap();
+		let mut expected = Vec::new();
+		writeln!(expected, "Friedman estimate: 4.30\nFriedman estimate: none").unwrap();
+		assert_eq!(out, expected);
+	}
+
+	#[test]
+	fn print_period_sweep_result_json() {
+		let mut out = Vec::new();
+		print_period_sweep_result(
+			&to_periods(vec![(1, 0.25), (2, 0.5)]),
+			&OutputFormat::Json, &mut out).unwrap();
+		let mut expected = Vec::new();
+		writeln!(
+			expected,
+			"{{\"periods\":[{{\"period\":1,\"index_of_coincidence\":0.25}},\
+			{{\"period\":2,\"index_of_coincidence\":0.5}}]}}").unwrap();
+		assert_eq!(out, expected);
+	}
+
+	#[test]
+	fn print_friedman_test_result_json() {
+		let mut out = Vec::new();
+		print_friedman_test_result(Some(4.5), &OutputFormat::Json, &mut out).unwrap();
+		print_friedman_test_result(None, &OutputFormat::Json, &mut out).unwrap();
+		let mut expected = Vec::new();
+		writeln!(expected, "{{\"friedman_estimate\":4.5}}\n{{\"friedman_estimate\":null}}").unwrap();
+		assert_eq!(out, expected);
+	}
+
+	#[test]
+	fn print_period_sweep_result_csv() {
+		let mut out = Vec::new();
+		print_period_sweep_result(
+			&to_periods(vec![(1, 0.25), (2, 0.5)]),
+			&OutputFormat::Csv, &mut out).unwrap();
+		let mut expected = Vec::new();
+		writeln!(expected, "period,index_of_coincidence\n1,0.25\n2,0.5").unwrap();
+		assert_eq!(out, expected);
+	}
+}
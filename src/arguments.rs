@@ -1,11 +1,57 @@
 // TODO: use argument parsing library
+use std::io;
+use std::io::IsTerminal;
+use cryptolytics::input::STDIN_FILENAME;
+use cryptolytics::input::MMAP_THRESHOLD;
+use cryptolytics::input::MmapMode;
+use cryptolytics::input::InputFormat;
+use cryptolytics::input::Endian;
+use cryptolytics::output::OutputFormat;
+use cryptolytics::scoring::Alphabet;
 
+/// Analyses which consume the input chunk by chunk
 #[derive(PartialEq, Debug)]
-pub enum AnalyzeMethod {
-	None,
+pub enum ChunkMethod {
 	MinMax,
 	FrequencyAnalysis,
+	// Window size (0 for the whole input) and step
+	Entropy(usize, usize),
+}
+
+/// Analyses which need the whole input at once
+#[derive(PartialEq, Debug)]
+pub enum WholeMethod {
+	// N-gram size other than 1 and whether the n-grams overlap
+	NgramFrequencyAnalysis(usize, bool),
 	KasiskiExamination(usize),
+	IndexOfCoincidence,
+	PeriodSweep(usize),
+	ChiSquared,
+	// Alphabet and number of candidates
+	Caesar(Alphabet, usize),
+	// Alphabet, maximum period and key length (0 to estimate it)
+	Vigenere(Alphabet, usize, usize),
+	// Maximum key length and key length (0 to estimate it)
+	XorRepeating(usize, usize),
+	// Number of candidates
+	XorSingle(usize),
+	// Number of hill climbing restarts
+	Substitution(usize),
+	Score,
+	// Maximum key length, key length (0 for every length up to the
+	// maximum) and number of candidates
+	Transposition(usize, usize, usize),
+	// Maximum number of rails and columns and number of candidates
+	RailFence(usize, usize),
+	// Language model file to write, name of the language and alphabet
+	Train(String, String, Alphabet),
+}
+
+#[derive(PartialEq, Debug)]
+pub enum AnalyzeMethod {
+	None,
+	Chunks(ChunkMethod),
+	Whole(WholeMethod),
 }
 
 #[derive(PartialEq, Debug)]
@@ -21,23 +67,75 @@ pub enum Sizes {
 pub struct Action {
 	pub keep_every: usize,
 	pub skip_first: usize,
+	pub length: usize,
+	pub mmap: MmapMode,
 	pub method: AnalyzeMethod,
 	pub size: Sizes,
+	pub input_format: InputFormat,
+	pub endian: Endian,
+	pub output: OutputFormat,
+	pub language_model: Option<String>,
 	pub filename: String,
 	pub help: bool,
 }
 
 const DEFAULT_KASISKI_LEN: usize = 5;
+const DEFAULT_NGRAM: usize = 1;
+const DEFAULT_MAX_PERIOD: usize = 20;
+const DEFAULT_WINDOW: usize = 0;
+const DEFAULT_TOP: usize = 5;
+const DEFAULT_ALPHABET: &str = "letters";
+const DEFAULT_KEY_LENGTH: usize = 0;
+const DEFAULT_RESTARTS: usize = 20;
+const DEFAULT_MODEL_NAME: &str = "custom";
+const DEFAULT_MAX_COLUMNS: usize = 10;
+const DEFAULT_MAX_RAILS: usize = 10;
 const DEFAULT_SIZE: &str = "1";
 const DEFAULT_KEEP_EVERY: &str = "1";
 const DEFAULT_SKIP_FIRST: &str = "0";
+const DEFAULT_LENGTH: &str = "0";
+const DEFAULT_INPUT_FORMAT: &str = "raw";
+const DEFAULT_ENDIAN: &str = "big";
+const DEFAULT_OUTPUT: &str = "text";
 const STR_MIN_MAX: &str = "min_max";
 const STR_FREQUENCY_ANALYSIS: &str = "frequency_analysis";
 const STR_KASISKI_EXAMINATION: &str = "kasiski_examination";
+const STR_INDEX_OF_COINCIDENCE: &str = "index_of_coincidence";
+const STR_PERIOD_SWEEP: &str = "period_sweep";
+const STR_ENTROPY: &str = "entropy";
+const STR_CHI_SQUARED: &str = "chi_squared";
+const STR_CAESAR: &str = "caesar";
+const STR_VIGENERE: &str = "vigenere";
+const STR_XOR_REPEATING: &str = "xor_repeating";
+const STR_XOR_SINGLE: &str = "xor_single";
+const STR_SUBSTITUTION: &str = "substitution";
+const STR_SCORE: &str = "score";
+const STR_TRAIN: &str = "train";
+const STR_TRANSPOSITION: &str = "transposition";
+const STR_RAIL_FENCE: &str = "rail_fence";
+const STR_NGRAM: &str = "--ngram";
+const STR_NON_OVERLAPPING: &str = "--non-overlapping";
 const STR_MIN_LENGTH: &str = "--min-length";
+const STR_MAX_PERIOD: &str = "--max-period";
+const STR_WINDOW: &str = "--window";
+const STR_STEP: &str = "--step";
+const STR_ALPHABET: &str = "--alphabet";
+const STR_TOP: &str = "--top";
+const STR_KEY_LENGTH: &str = "--key-length";
+const STR_RESTARTS: &str = "--restarts";
+const STR_SAVE: &str = "--save";
+const STR_MAX_RAILS: &str = "--max-rails";
+const STR_NAME: &str = "--name";
 const STR_BYTES: &str = "--bytes";
 const STR_KEEP_EVERY: &str = "--keep-every";
 const STR_SKIP_FIRST: &str = "--skip-first";
+const STR_LENGTH: &str = "--length";
+const STR_MMAP: &str = "--mmap";
+const STR_NO_MMAP: &str = "--no-mmap";
+const STR_INPUT_FORMAT: &str = "--input-format";
+const STR_ENDIAN: &str = "--endian";
+const STR_OUTPUT: &str = "--output";
+const STR_LANGUAGE_MODEL: &str = "--language-model";
 
 fn parse_sizes(arg: Option<&String>) -> Result<Sizes, String> {
 	let error = Err("Bytes parameter is invalid".to_string());
@@ -57,6 +155,70 @@ fn parse_sizes(arg: Option<&String>) -> Result<Sizes, String> {
 	};
 }
 
+fn parse_input_format(arg: Option<&String>) -> Result<InputFormat, String> {
+	let error = Err(format!("{} is invalid", STR_INPUT_FORMAT));
+	let s = match arg {
+		Some(t) => t,
+		_ => {
+			return error;
+		}
+	};
+	return match &*s.to_string() {
+		"raw" => Ok(InputFormat::Raw),
+		"hex" => Ok(InputFormat::Hex),
+		"base64" => Ok(InputFormat::Base64),
+		"base32" => Ok(InputFormat::Base32),
+		_ => error,
+	};
+}
+
+fn parse_endian(arg: Option<&String>) -> Result<Endian, String> {
+	let error = Err(format!("{} is invalid", STR_ENDIAN));
+	let s = match arg {
+		Some(t) => t,
+		_ => {
+			return error;
+		}
+	};
+	return match &*s.to_string() {
+		"big" => Ok(Endian::Big),
+		"little" => Ok(Endian::Little),
+		_ => error,
+	};
+}
+
+fn parse_output(arg: Option<&String>) -> Result<OutputFormat, String> {
+	let error = Err(format!("{} is invalid", STR_OUTPUT));
+	let s = match arg {
+		Some(t) => t,
+		_ => {
+			return error;
+		}
+	};
+	return match &*s.to_string() {
+		"text" => Ok(OutputFormat::Text),
+		"json" => Ok(OutputFormat::Json),
+		"csv" => Ok(OutputFormat::Csv),
+		_ => error,
+	};
+}
+
+fn parse_alphabet(arg: Option<&String>) -> Result<Alphabet, String> {
+	let error = Err(format!("{} is invalid", STR_ALPHABET));
+	let s = match arg {
+		Some(t) => t,
+		_ => {
+			return error;
+		}
+	};
+	return match &*s.to_string() {
+		"letters" => Ok(Alphabet::Letters),
+		"printable" => Ok(Alphabet::Printable),
+		"bytes" => Ok(Alphabet::Bytes),
+		_ => error,
+	};
+}
+
 fn parse_usize(arg: Option<&String>, error: &String) -> Result<usize, String> {
 	match arg.map(|s| s.parse::<usize>()) {
 		Some(Ok(l)) => {
@@ -68,10 +230,14 @@ fn parse_usize(arg: Option<&String>, error: &String) -> Result<usize, String> {
 	};
 }
 
+fn parse_string(arg: Option<&String>, name: &str) -> Result<String, String> {
+	return arg.cloned().ok_or_else(|| format!("{} is invalid", name));
+}
+
 fn parse_kasiski_examination_params(args: &Vec<String>, pos: usize) ->
 		Result<(AnalyzeMethod, usize), String> {
 	if pos >= args.len() {
-		return Ok((AnalyzeMethod::KasiskiExamination(DEFAULT_KASISKI_LEN), 0));
+		return Ok((AnalyzeMethod::Whole(WholeMethod::KasiskiExamination(DEFAULT_KASISKI_LEN)), 0));
 	}
 	let arg = &args[pos];
 	return match Some(&*arg.to_string()) {
@@ -79,18 +245,311 @@ fn parse_kasiski_examination_params(args: &Vec<String>, pos: usize) ->
 			let m = parse_usize(
 				args.get(pos + 1),
 				&format!("{} is invalid", STR_MIN_LENGTH))?;
-			Ok((AnalyzeMethod::KasiskiExamination(m), 2))
+			Ok((AnalyzeMethod::Whole(WholeMethod::KasiskiExamination(m)), 2))
+		},
+		_ => Ok((AnalyzeMethod::Whole(WholeMethod::KasiskiExamination(DEFAULT_KASISKI_LEN)), 0)),
+	};
+}
+
+fn parse_period_sweep_params(args: &Vec<String>, pos: usize) ->
+		Result<(AnalyzeMethod, usize), String> {
+	if pos >= args.len() {
+		return Ok((AnalyzeMethod::Whole(WholeMethod::PeriodSweep(DEFAULT_MAX_PERIOD)), 0));
+	}
+	let arg = &args[pos];
+	return match Some(&*arg.to_string()) {
+		Some(STR_MAX_PERIOD) => {
+			let m = parse_usize(
+				args.get(pos + 1),
+				&format!("{} is invalid", STR_MAX_PERIOD))?;
+			Ok((AnalyzeMethod::Whole(WholeMethod::PeriodSweep(m)), 2))
 		},
-		_ => Ok((AnalyzeMethod::KasiskiExamination(DEFAULT_KASISKI_LEN), 0)),
+		_ => Ok((AnalyzeMethod::Whole(WholeMethod::PeriodSweep(DEFAULT_MAX_PERIOD)), 0)),
+	};
+}
+
+// Without a step, the windows do not overlap
+fn parse_entropy_params(args: &Vec<String>, pos: usize) ->
+		Result<(AnalyzeMethod, usize), String> {
+	let window_error = format!("{} is invalid", STR_WINDOW);
+	let step_error = format!("{} is invalid", STR_STEP);
+	let mut window = DEFAULT_WINDOW;
+	let mut step: Option<usize> = None;
+	let mut p = pos;
+	while p < args.len() {
+		match Some(&*args[p].to_string()) {
+			Some(STR_WINDOW) => {
+				window = parse_usize(args.get(p + 1), &window_error)?;
+			},
+			Some(STR_STEP) => {
+				let s = parse_usize(args.get(p + 1), &step_error)?;
+				if s == 0 {
+					return Err(step_error);
+				}
+				step = Some(s);
+			},
+			_ => {
+				break;
+			},
+		}
+		p += 2;
+	}
+	if window == 0 && step.is_some() {
+		return Err(format!("{} needs {}", STR_STEP, STR_WINDOW));
+	}
+	let method = ChunkMethod::Entropy(window, step.unwrap_or(window));
+	return Ok((AnalyzeMethod::Chunks(method), p - pos));
+}
+
+fn parse_caesar_params(args: &Vec<String>, pos: usize) ->
+		Result<(AnalyzeMethod, usize), String> {
+	let top_error = format!("{} is invalid", STR_TOP);
+	let mut alphabet = parse_alphabet(Some(&DEFAULT_ALPHABET.to_string()))?;
+	let mut top = DEFAULT_TOP;
+	let mut p = pos;
+	while p < args.len() {
+		match Some(&*args[p].to_string()) {
+			Some(STR_ALPHABET) => {
+				alphabet = parse_alphabet(args.get(p + 1))?;
+			},
+			Some(STR_TOP) => {
+				top = parse_usize(args.get(p + 1), &top_error)?;
+			},
+			_ => {
+				break;
+			},
+		}
+		p += 2;
+	}
+	return Ok((AnalyzeMethod::Whole(WholeMethod::Caesar(alphabet, top)), p - pos));
+}
+
+fn parse_frequency_analysis_params(args: &Vec<String>, pos: usize) ->
+		Result<(AnalyzeMethod, usize), String> {
+	let ngram_error = format!("{} is invalid", STR_NGRAM);
+	let mut ngram = DEFAULT_NGRAM;
+	let mut overlapping = true;
+	let mut p = pos;
+	while p < args.len() {
+		match Some(&*args[p].to_string()) {
+			Some(STR_NGRAM) => {
+				ngram = parse_usize(args.get(p + 1), &ngram_error)?;
+				if ngram == 0 {
+					return Err(ngram_error);
+				}
+				p += 2;
+			},
+			Some(STR_NON_OVERLAPPING) => {
+				overlapping = false;
+				p += 1;
+			},
+			_ => {
+				break;
+			},
+		}
+	}
+	if ngram == 1 {
+		return Ok((AnalyzeMethod::Chunks(ChunkMethod::FrequencyAnalysis), p - pos));
+	}
+	let method = WholeMethod::NgramFrequencyAnalysis(ngram, overlapping);
+	return Ok((AnalyzeMethod::Whole(method), p - pos));
+}
+
+fn parse_vigenere_params(args: &Vec<String>, pos: usize) ->
+		Result<(AnalyzeMethod, usize), String> {
+	let max_period_error = format!("{} is invalid", STR_MAX_PERIOD);
+	let key_length_error = format!("{} is invalid", STR_KEY_LENGTH);
+	let mut alphabet = parse_alphabet(Some(&DEFAULT_ALPHABET.to_string()))?;
+	let mut max_period = DEFAULT_MAX_PERIOD;
+	let mut key_length = DEFAULT_KEY_LENGTH;
+	let mut p = pos;
+	while p < args.len() {
+		match Some(&*args[p].to_string()) {
+			Some(STR_ALPHABET) => {
+				alphabet = parse_alphabet(args.get(p + 1))?;
+			},
+			Some(STR_MAX_PERIOD) => {
+				max_period = parse_usize(args.get(p + 1), &max_period_error)?;
+			},
+			Some(STR_KEY_LENGTH) => {
+				key_length = parse_usize(args.get(p + 1), &key_length_error)?;
+			},
+			_ => {
+				break;
+			},
+		}
+		p += 2;
+	}
+	let method = WholeMethod::Vigenere(alphabet, max_period, key_length);
+	return Ok((AnalyzeMethod::Whole(method), p - pos));
+}
+
+fn parse_xor_repeating_params(args: &Vec<String>, pos: usize) ->
+		Result<(AnalyzeMethod, usize), String> {
+	let max_period_error = format!("{} is invalid", STR_MAX_PERIOD);
+	let key_length_error = format!("{} is invalid", STR_KEY_LENGTH);
+	let mut max_period = DEFAULT_MAX_PERIOD;
+	let mut key_length = DEFAULT_KEY_LENGTH;
+	let mut p = pos;
+	while p < args.len() {
+		match Some(&*args[p].to_string()) {
+			Some(STR_MAX_PERIOD) => {
+				max_period = parse_usize(args.get(p + 1), &max_period_error)?;
+			},
+			Some(STR_KEY_LENGTH) => {
+				key_length = parse_usize(args.get(p + 1), &key_length_error)?;
+			},
+			_ => {
+				break;
+			},
+		}
+		p += 2;
+	}
+	return Ok((AnalyzeMethod::Whole(WholeMethod::XorRepeating(max_period, key_length)), p - pos));
+}
+
+fn parse_xor_single_params(args: &Vec<String>, pos: usize) ->
+		Result<(AnalyzeMethod, usize), String> {
+	let top_error = format!("{} is invalid", STR_TOP);
+	let mut top = DEFAULT_TOP;
+	let mut p = pos;
+	while p < args.len() {
+		match Some(&*args[p].to_string()) {
+			Some(STR_TOP) => {
+				top = parse_usize(args.get(p + 1), &top_error)?;
+			},
+			_ => {
+				break;
+			},
+		}
+		p += 2;
+	}
+	return Ok((AnalyzeMethod::Whole(WholeMethod::XorSingle(top)), p - pos));
+}
+
+fn parse_substitution_params(args: &Vec<String>, pos: usize) ->
+		Result<(AnalyzeMethod, usize), String> {
+	let restarts_error = format!("{} is invalid", STR_RESTARTS);
+	let mut restarts = DEFAULT_RESTARTS;
+	let mut p = pos;
+	while p < args.len() {
+		match Some(&*args[p].to_string()) {
+			Some(STR_RESTARTS) => {
+				restarts = parse_usize(args.get(p + 1), &restarts_error)?;
+			},
+			_ => {
+				break;
+			},
+		}
+		p += 2;
+	}
+	return Ok((AnalyzeMethod::Whole(WholeMethod::Substitution(restarts)), p - pos));
+}
+
+fn parse_transposition_params(args: &Vec<String>, pos: usize) ->
+		Result<(AnalyzeMethod, usize), String> {
+	let max_period_error = format!("{} is invalid", STR_MAX_PERIOD);
+	let key_length_error = format!("{} is invalid", STR_KEY_LENGTH);
+	let top_error = format!("{} is invalid", STR_TOP);
+	let mut max_period = DEFAULT_MAX_COLUMNS;
+	let mut key_length = DEFAULT_KEY_LENGTH;
+	let mut top = DEFAULT_TOP;
+	let mut p = pos;
+	while p < args.len() {
+		match Some(&*args[p].to_string()) {
+			Some(STR_MAX_PERIOD) => {
+				max_period = parse_usize(args.get(p + 1), &max_period_error)?;
+			},
+			Some(STR_KEY_LENGTH) => {
+				key_length = parse_usize(args.get(p + 1), &key_length_error)?;
+			},
+			Some(STR_TOP) => {
+				top = parse_usize(args.get(p + 1), &top_error)?;
+			},
+			_ => {
+				break;
+			},
+		}
+		p += 2;
+	}
+	if max_period < 2 {
+		return Err(max_period_error);
+	}
+	let method = WholeMethod::Transposition(max_period, key_length, top);
+	return Ok((AnalyzeMethod::Whole(method), p - pos));
+}
+
+fn parse_rail_fence_params(args: &Vec<String>, pos: usize) ->
+		Result<(AnalyzeMethod, usize), String> {
+	let max_rails_error = format!("{} is invalid", STR_MAX_RAILS);
+	let top_error = format!("{} is invalid", STR_TOP);
+	let mut max_rails = DEFAULT_MAX_RAILS;
+	let mut top = DEFAULT_TOP;
+	let mut p = pos;
+	while p < args.len() {
+		match Some(&*args[p].to_string()) {
+			Some(STR_MAX_RAILS) => {
+				max_rails = parse_usize(args.get(p + 1), &max_rails_error)?;
+			},
+			Some(STR_TOP) => {
+				top = parse_usize(args.get(p + 1), &top_error)?;
+			},
+			_ => {
+				break;
+			},
+		}
+		p += 2;
+	}
+	return Ok((AnalyzeMethod::Whole(WholeMethod::RailFence(max_rails, top)), p - pos));
+}
+
+fn parse_train_params(args: &Vec<String>, pos: usize) ->
+		Result<(AnalyzeMethod, usize), String> {
+	let mut save: Option<String> = None;
+	let mut name = DEFAULT_MODEL_NAME.to_string();
+	let mut alphabet = parse_alphabet(Some(&DEFAULT_ALPHABET.to_string()))?;
+	let mut p = pos;
+	while p < args.len() {
+		match Some(&*args[p].to_string()) {
+			Some(STR_SAVE) => {
+				save = Some(parse_string(args.get(p + 1), STR_SAVE)?);
+			},
+			Some(STR_NAME) => {
+				name = parse_string(args.get(p + 1), STR_NAME)?;
+			},
+			Some(STR_ALPHABET) => {
+				alphabet = parse_alphabet(args.get(p + 1))?;
+			},
+			_ => {
+				break;
+			},
+		}
+		p += 2;
+	}
+	return match save {
+		Some(s) => Ok((AnalyzeMethod::Whole(WholeMethod::Train(s, name, alphabet)), p - pos)),
+		None => Err(format!("{} needs {} FILE", STR_TRAIN, STR_SAVE)),
 	};
 }
 
-fn parse_optionals(args: &Vec<String>) -> Result<(Sizes, usize, usize, usize), String> {
+// size, skip_first, keep_every, length, mmap, input_format, endian, output,
+// language_model, position of method
+type Optionals = (
+	Sizes, usize, usize, usize, MmapMode, InputFormat, Endian, OutputFormat, Option<String>, usize);
+
+fn parse_optionals(args: &Vec<String>) -> Result<Optionals, String> {
 	let mut size: Sizes = parse_sizes(Some(&DEFAULT_SIZE.to_string()))?;
+	let mut input_format = parse_input_format(Some(&DEFAULT_INPUT_FORMAT.to_string()))?;
+	let mut endian = parse_endian(Some(&DEFAULT_ENDIAN.to_string()))?;
+	let mut output = parse_output(Some(&DEFAULT_OUTPUT.to_string()))?;
 	let keep_every_error = format!("{} is invalid", STR_KEEP_EVERY);
 	let mut keep_every = parse_usize(Some(&DEFAULT_KEEP_EVERY.to_string()), &keep_every_error)?;
 	let skip_first_error = format!("{} is invalid", STR_SKIP_FIRST);
 	let mut skip_first = parse_usize(Some(&DEFAULT_SKIP_FIRST.to_string()), &skip_first_error)?;
+	let length_error = format!("{} is invalid", STR_LENGTH);
+	let mut length = parse_usize(Some(&DEFAULT_LENGTH.to_string()), &length_error)?;
+	let mut mmap = MmapMode::Auto;
+	let mut language_model: Option<String> = None;
 
 	let mut pos = 1; // Skip binary
 	while pos < args.len() {
@@ -108,31 +567,67 @@ fn parse_optionals(args: &Vec<String>) -> Result<(Sizes, usize, usize, usize), S
 				skip_first = parse_usize(args.get(pos + 1), &skip_first_error)?;
 				pos += 1;
 			},
+			Some(STR_LENGTH) => {
+				length = parse_usize(args.get(pos + 1), &length_error)?;
+				pos += 1;
+			},
+			Some(STR_MMAP) => {
+				mmap = MmapMode::Always;
+			},
+			Some(STR_NO_MMAP) => {
+				mmap = MmapMode::Never;
+			},
+			Some(STR_INPUT_FORMAT) => {
+				input_format = parse_input_format(args.get(pos + 1))?;
+				pos += 1;
+			},
+			Some(STR_ENDIAN) => {
+				endian = parse_endian(args.get(pos + 1))?;
+				pos += 1;
+			},
+			Some(STR_OUTPUT) => {
+				output = parse_output(args.get(pos + 1))?;
+				pos += 1;
+			},
+			Some(STR_LANGUAGE_MODEL) => {
+				language_model = Some(parse_string(args.get(pos + 1), STR_LANGUAGE_MODEL)?);
+				pos += 1;
+			},
 			_ => {
 				break;
 			},
 		}
 		pos += 1;
 	}
-	return Ok((size, skip_first, keep_every, pos));
+	return Ok((
+		size, skip_first, keep_every, length, mmap, input_format, endian, output, language_model,
+		pos));
 }
 
 pub fn parse_args(args: &Vec<String>) -> Result<Action, String> {
+	return parse_args_with_stdin(args, !io::stdin().is_terminal());
+}
+
+// Without a file, the input is read from stdin if it is not a terminal
+fn parse_args_with_stdin(args: &Vec<String>, stdin_readable: bool) -> Result<Action, String> {
 	let mut method: AnalyzeMethod = AnalyzeMethod::None;
 	let mut file: Option<String> = None;
 	let mut help = false;
 	let mut method_set_count = 0;
 
-	let (size, skip_first, keep_every, mut pos) = parse_optionals(&args)?;
+	let (size, skip_first, keep_every, length, mmap, input_format, endian, output, language_model,
+	     mut pos) = parse_optionals(args)?;
 	while pos < args.len() {
 		let arg = &args[pos];
 		match Some(&*arg.to_string()) {
 			Some(STR_MIN_MAX) => {
-				method = AnalyzeMethod::MinMax;
+				method = AnalyzeMethod::Chunks(ChunkMethod::MinMax);
 				method_set_count += 1;
 			},
 			Some(STR_FREQUENCY_ANALYSIS) => {
-				method = AnalyzeMethod::FrequencyAnalysis;
+				let (m, a) = parse_frequency_analysis_params(args, pos + 1)?;
+				method = m;
+				pos += a;
 				method_set_count += 1;
 			},
 			Some(STR_KASISKI_EXAMINATION) => {
@@ -141,6 +636,78 @@ pub fn parse_args(args: &Vec<String>) -> Result<Action, String> {
 				pos += a;
 				method_set_count += 1;
 			},
+			Some(STR_INDEX_OF_COINCIDENCE) => {
+				method = AnalyzeMethod::Whole(WholeMethod::IndexOfCoincidence);
+				method_set_count += 1;
+			},
+			Some(STR_PERIOD_SWEEP) => {
+				let (m, a) = parse_period_sweep_params(args, pos + 1)?;
+				method = m;
+				pos += a;
+				method_set_count += 1;
+			},
+			Some(STR_ENTROPY) => {
+				let (m, a) = parse_entropy_params(args, pos + 1)?;
+				method = m;
+				pos += a;
+				method_set_count += 1;
+			},
+			Some(STR_CHI_SQUARED) => {
+				method = AnalyzeMethod::Whole(WholeMethod::ChiSquared);
+				method_set_count += 1;
+			},
+			Some(STR_CAESAR) => {
+				let (m, a) = parse_caesar_params(args, pos + 1)?;
+				method = m;
+				pos += a;
+				method_set_count += 1;
+			},
+			Some(STR_VIGENERE) => {
+				let (m, a) = parse_vigenere_params(args, pos + 1)?;
+				method = m;
+				pos += a;
+				method_set_count += 1;
+			},
+			Some(STR_XOR_REPEATING) => {
+				let (m, a) = parse_xor_repeating_params(args, pos + 1)?;
+				method = m;
+				pos += a;
+				method_set_count += 1;
+			},
+			Some(STR_XOR_SINGLE) => {
+				let (m, a) = parse_xor_single_params(args, pos + 1)?;
+				method = m;
+				pos += a;
+				method_set_count += 1;
+			},
+			Some(STR_SUBSTITUTION) => {
+				let (m, a) = parse_substitution_params(args, pos + 1)?;
+				method = m;
+				pos += a;
+				method_set_count += 1;
+			},
+			Some(STR_SCORE) => {
+				method = AnalyzeMethod::Whole(WholeMethod::Score);
+				method_set_count += 1;
+			},
+			Some(STR_TRANSPOSITION) => {
+				let (m, a) = parse_transposition_params(args, pos + 1)?;
+				method = m;
+				pos += a;
+				method_set_count += 1;
+			},
+			Some(STR_RAIL_FENCE) => {
+				let (m, a) = parse_rail_fence_params(args, pos + 1)?;
+				method = m;
+				pos += a;
+				method_set_count += 1;
+			},
+			Some(STR_TRAIN) => {
+				let (m, a) = parse_train_params(args, pos + 1)?;
+				method = m;
+				pos += a;
+				method_set_count += 1;
+			},
 			Some("-h") => {
 				help = true;
 			},
@@ -164,13 +731,30 @@ pub fn parse_args(args: &Vec<String>) -> Result<Action, String> {
 	if help {
 		file = Some("".to_string());
 	}
+	if file.is_none() && stdin_readable {
+		file = Some(STDIN_FILENAME.to_string());
+	}
+	if mmap == MmapMode::Always && !help {
+		if input_format != InputFormat::Raw {
+			return Err(format!("{} needs {} raw", STR_MMAP, STR_INPUT_FORMAT));
+		}
+		if file.as_deref() == Some(STDIN_FILENAME) {
+			return Err(format!("{} cannot map standard input", STR_MMAP));
+		}
+	}
 	return match file {
 		Some(f) => Ok(Action {
 			skip_first: skip_first,
 			keep_every: keep_every,
+			length: length,
+			mmap: mmap,
 			method: method,
 			filename: f,
 			size: size,
+			input_format: input_format,
+			endian: endian,
+			output: output,
+			language_model: language_model,
 			help: help,
 		}),
 		_ => Err("No file specified".to_string()),
@@ -183,6 +767,9 @@ pub fn help(exe: &String) {
 		\n\
 		Usage: {exe} [OPTIONAL ARGUMENTS] [METHOD [METHOD PARAMETERS] FILE\n\
 		\n\
+		With FILE {stdin}, or without FILE if stdin is not a terminal,\n\
+		the input is read from stdin.\n\
+		\n\
 		Optional arguments:\n\
 		\t-h|--help:    Print this help message\n\
 		\t{bytes}:      How many bytes to group together.\n\
@@ -191,29 +778,144 @@ pub fn help(exe: &String) {
 		(Default: {skip_first_default})\n\
 		\t{keep_every}: Keep only every KEEP_EVERY-th BYTES-len bytes\
 		(Default: {keep_every_default}\n\
+		\t{length}:     Use only the first LENGTH words after skipping\n\
+		\t              and filtering, 0 for all (Default: {length_default})\n\
+		\t{mmap}:       Map the raw input file into memory instead of reading\n\
+		\t              it, done by default for files of at least {mmap_threshold} MiB\n\
+		\t{no_mmap}:    Never map the input file into memory\n\
+		\t{input_format}: Decode the input before the analysis\n\
+		\t              raw, hex, base64, base32 (Default: {input_format_default})\n\
+		\t{endian}:     Byte order of the BYTES-len words\n\
+		\t              big, little (Default: {endian_default})\n\
+		\t{output}:     Output format, json writes one object per line,\n\
+		\t              csv only the main table of the method\n\
+		\t              text, json, csv (Default: {output_default})\n\
+		\t{language_model}: Language model file written by {train}, compared\n\
+		\t              by {chi_squared} and {score}, used by {substitution},\n\
+		\t              {transposition} and {rail_fence}\n\
 		\n\
 		Methods:\n\
 		\t{min_max}                 Show range of bytes\n\
 		\t{frequency_analysis}      Count occurence of bytes\n\
-		\t{kasiski_examination}     Show duplicate words\n\
-		\t\t{min_length}    Minimum word length, Default: {kasiski_len}",
+		\t\t{ngram}         Count n-grams of NGRAM words, Default: {ngram_default}\n\
+		\t\t{non_overlapping} Split the input into n-grams instead of\n\
+		\t\t                starting one at every word\n\
+		\t{kasiski_examination}     Show repeated words and key length candidates\n\
+		\t\t{min_length}    Minimum word length, Default: {kasiski_len}\n\
+		\t{index_of_coincidence}    Calculate index of coincidence\n\
+		\t{period_sweep}            Average index of coincidence of the columns\n\
+		\t                        for every period and Friedman estimate\n\
+		\t\t{max_period}    Maximum period, Default: {max_period_default}\n\
+		\t{entropy}                 Shannon entropy in bits per BYTES-len word\n\
+		\t\t{window}        Entropy of every window of WINDOW words\n\
+		\t\t                instead of the whole input\n\
+		\t\t{step}          Move the window STEP words, Default: WINDOW\n\
+		\t{chi_squared}             Compare the letter frequencies with english,\n\
+		\t                        german, french and spanish\n\
+		\t{caesar}                  Try every shift, ranked by english chi-squared\n\
+		\t\t{alphabet}      letters, printable, bytes, Default: {alphabet_default}\n\
+		\t\t{top}           Number of candidates, Default: {top_default}\n\
+		\t{vigenere}                Recover key and plaintext of a vigenere cipher\n\
+		\t\t{alphabet}      letters, printable, bytes, Default: {alphabet_default}\n\
+		\t\t{max_period}    Maximum key length, Default: {max_period_default}\n\
+		\t\t{key_length}    Key length, Default: estimated\n\
+		\t{xor_repeating}           Recover key and plaintext of a repeating-key\n\
+		\t                        xor, needs {bytes} 1\n\
+		\t\t{max_period}    Maximum key length, Default: {max_period_default}\n\
+		\t\t{key_length}    Key length, Default: ranked by hamming distance\n\
+		\t{xor_single}              Try the keys of one BYTES-len word, each byte\n\
+		\t                        solved on its own, ranked by english\n\
+		\t                        chi-squared, with the printable ratio\n\
+		\t\t{top}           Number of candidates, Default: {top_default}\n\
+		\t{substitution}            Solve a monoalphabetic substitution of the\n\
+		\t                        letters with english quadgrams\n\
+		\t\t{restarts}      Hill climbing restarts, Default: {restarts_default}\n\
+		\t{score}                   Rate how much the letters look like plaintext\n\
+		\t                        of english, german, french and spanish\n\
+		\t{transposition}           Detect a transposition by its letter and bigram\n\
+		\t                        statistics and solve a columnar transposition\n\
+		\t\t{max_period}    Maximum key length, Default: {max_columns_default}\n\
+		\t\t{key_length}    Key length, Default: every length up to the maximum\n\
+		\t\t{top}           Number of candidates, Default: {top_default}\n\
+		\t{rail_fence}              Try every rail fence and every offset, and\n\
+		\t                        routes through grids, ranked by fitness\n\
+		\t\t{max_rails}     Maximum number of rails and grid columns,\n\
+		\t\t                Default: {max_rails_default}\n\
+		\t\t{top}           Number of candidates, Default: {top_default}\n\
+		\t{train}                   Count the n-grams of a plaintext corpus FILE\n\
+		\t\t{save}          Language model file to write\n\
+		\t\t{name}          Name of the language, Default: {model_name_default}\n\
+		\t\t{alphabet}      letters, printable, bytes, Default: {alphabet_default}\n\
+		\t\t                Only letter models work with {chi_squared},\n\
+		\t\t                {substitution}, {transposition} and {rail_fence}",
 		exe=exe,
+		stdin=STDIN_FILENAME,
 		kasiski_len=DEFAULT_KASISKI_LEN,
 		frequency_analysis=STR_FREQUENCY_ANALYSIS,
+		ngram=STR_NGRAM,
+		ngram_default=DEFAULT_NGRAM,
+		non_overlapping=STR_NON_OVERLAPPING,
 		min_max=STR_MIN_MAX,
 		min_length=STR_MIN_LENGTH,
 		kasiski_examination=STR_KASISKI_EXAMINATION,
+		index_of_coincidence=STR_INDEX_OF_COINCIDENCE,
+		period_sweep=STR_PERIOD_SWEEP,
+		max_period=STR_MAX_PERIOD,
+		max_period_default=DEFAULT_MAX_PERIOD,
+		entropy=STR_ENTROPY,
+		window=STR_WINDOW,
+		step=STR_STEP,
+		chi_squared=STR_CHI_SQUARED,
+		caesar=STR_CAESAR,
+		alphabet=STR_ALPHABET,
+		alphabet_default=DEFAULT_ALPHABET,
+		top=STR_TOP,
+		top_default=DEFAULT_TOP,
+		vigenere=STR_VIGENERE,
+		key_length=STR_KEY_LENGTH,
+		xor_repeating=STR_XOR_REPEATING,
+		xor_single=STR_XOR_SINGLE,
+		substitution=STR_SUBSTITUTION,
+		restarts=STR_RESTARTS,
+		restarts_default=DEFAULT_RESTARTS,
+		score=STR_SCORE,
+		train=STR_TRAIN,
+		rail_fence=STR_RAIL_FENCE,
+		max_rails=STR_MAX_RAILS,
+		max_rails_default=DEFAULT_MAX_RAILS,
+		transposition=STR_TRANSPOSITION,
+		max_columns_default=DEFAULT_MAX_COLUMNS,
+		save=STR_SAVE,
+		name=STR_NAME,
+		model_name_default=DEFAULT_MODEL_NAME,
+		language_model=STR_LANGUAGE_MODEL,
 		bytes=STR_BYTES,
 		keep_every=STR_KEEP_EVERY,
 		keep_every_default=DEFAULT_KEEP_EVERY,
 		skip_first=STR_SKIP_FIRST,
 		skip_first_default=DEFAULT_SKIP_FIRST,
+		length=STR_LENGTH,
+		length_default=DEFAULT_LENGTH,
+		mmap=STR_MMAP,
+		no_mmap=STR_NO_MMAP,
+		mmap_threshold=MMAP_THRESHOLD >> 20,
+		input_format=STR_INPUT_FORMAT,
+		input_format_default=DEFAULT_INPUT_FORMAT,
+		endian=STR_ENDIAN,
+		endian_default=DEFAULT_ENDIAN,
+		output=STR_OUTPUT,
+		output_default=DEFAULT_OUTPUT,
 		size=DEFAULT_SIZE);
 }
 
 #[cfg(test)]
 mod tests {
 	use crate::arguments;
+	use cryptolytics::input::InputFormat;
+	use cryptolytics::input::Endian;
+	use cryptolytics::input::MmapMode;
+	use cryptolytics::scoring::Alphabet;
+	use cryptolytics::output::OutputFormat;
 
 	#[test]
 	fn parse_sizes() {
@@ -240,6 +942,79 @@ mod tests {
 			Err("Bytes parameter is invalid".to_string()));
 	}
 
+	#[test]
+	fn parse_input_format() {
+		assert_eq!(
+			arguments::parse_input_format(Some(&"raw".to_string())).unwrap(),
+			InputFormat::Raw);
+		assert_eq!(
+			arguments::parse_input_format(Some(&"hex".to_string())).unwrap(),
+			InputFormat::Hex);
+		assert_eq!(
+			arguments::parse_input_format(Some(&"base64".to_string())).unwrap(),
+			InputFormat::Base64);
+		assert_eq!(
+			arguments::parse_input_format(Some(&"base32".to_string())).unwrap(),
+			InputFormat::Base32);
+		assert_eq!(
+			arguments::parse_input_format(None),
+			Err("--input-format is invalid".to_string()));
+		assert_eq!(
+			arguments::parse_input_format(Some(&"b64".to_string())),
+			Err("--input-format is invalid".to_string()));
+	}
+
+	#[test]
+	fn parse_endian() {
+		assert_eq!(
+			arguments::parse_endian(Some(&"big".to_string())).unwrap(),
+			Endian::Big);
+		assert_eq!(
+			arguments::parse_endian(Some(&"little".to_string())).unwrap(),
+			Endian::Little);
+		assert_eq!(
+			arguments::parse_endian(None),
+			Err("--endian is invalid".to_string()));
+		assert_eq!(
+			arguments::parse_endian(Some(&"middle".to_string())),
+			Err("--endian is invalid".to_string()));
+	}
+
+	#[test]
+	fn parse_output() {
+		assert_eq!(
+			arguments::parse_output(Some(&"text".to_string())).unwrap(),
+			OutputFormat::Text);
+		assert_eq!(
+			arguments::parse_output(Some(&"json".to_string())).unwrap(),
+			OutputFormat::Json);
+		assert_eq!(
+			arguments::parse_output(Some(&"csv".to_string())).unwrap(),
+			OutputFormat::Csv);
+		assert_eq!(
+			arguments::parse_output(None),
+			Err("--output is invalid".to_string()));
+		assert_eq!(
+			arguments::parse_output(Some(&"xml".to_string())),
+			Err("--output is invalid".to_string()));
+	}
+
+	#[test]
+	fn parse_alphabet() {
+		assert_eq!(
+			arguments::parse_alphabet(Some(&"letters".to_string())).unwrap(),
+			Alphabet::Letters);
+		assert_eq!(
+			arguments::parse_alphabet(Some(&"printable".to_string())).unwrap(),
+			Alphabet::Printable);
+		assert_eq!(
+			arguments::parse_alphabet(Some(&"bytes".to_string())).unwrap(),
+			Alphabet::Bytes);
+		assert_eq!(
+			arguments::parse_alphabet(None),
+			Err("--alphabet is invalid".to_string()));
+	}
+
 	#[test]
 	fn parse_usize() {
 		fn e() -> String {
@@ -275,25 +1050,296 @@ mod tests {
 		let v = vec_str_conv(vec!["a", "--min-length", "72"]);
 		assert_eq!(
 			arguments::parse_kasiski_examination_params(&v, 1),
-			Ok((arguments::AnalyzeMethod::KasiskiExamination(72), 2)));
+			Ok((arguments::AnalyzeMethod::Whole(arguments::WholeMethod::KasiskiExamination(72)), 2)));
 		assert_eq!(
 			arguments::parse_kasiski_examination_params(&v, 2),
-			Ok((arguments::AnalyzeMethod::KasiskiExamination(5), 0)));
+			Ok((arguments::AnalyzeMethod::Whole(arguments::WholeMethod::KasiskiExamination(5)), 0)));
 		assert_eq!(
 			arguments::parse_kasiski_examination_params(&v, 4),
-			Ok((arguments::AnalyzeMethod::KasiskiExamination(5), 0)));
+			Ok((arguments::AnalyzeMethod::Whole(arguments::WholeMethod::KasiskiExamination(5)), 0)));
 		let err_v = vec_str_conv(vec!["--min-length", "b"]);
 		assert_eq!(
 			arguments::parse_kasiski_examination_params(&err_v, 0),
 			Err("--min-length is invalid".to_string()));
 	}
 
+	#[test]
+	fn parse_period_sweep_params() {
+		let v = vec_str_conv(vec!["a", "--max-period", "12"]);
+		assert_eq!(
+			arguments::parse_period_sweep_params(&v, 1),
+			Ok((arguments::AnalyzeMethod::Whole(arguments::WholeMethod::PeriodSweep(12)), 2)));
+		assert_eq!(
+			arguments::parse_period_sweep_params(&v, 2),
+			Ok((arguments::AnalyzeMethod::Whole(arguments::WholeMethod::PeriodSweep(20)), 0)));
+		assert_eq!(
+			arguments::parse_period_sweep_params(&v, 4),
+			Ok((arguments::AnalyzeMethod::Whole(arguments::WholeMethod::PeriodSweep(20)), 0)));
+		let err_v = vec_str_conv(vec!["--max-period", "b"]);
+		assert_eq!(
+			arguments::parse_period_sweep_params(&err_v, 0),
+			Err("--max-period is invalid".to_string()));
+	}
+
+	#[test]
+	fn parse_entropy_params() {
+		let v = vec_str_conv(vec!["a", "--window", "256", "--step", "16", "f"]);
+		assert_eq!(
+			arguments::parse_entropy_params(&v, 1),
+			Ok((arguments::AnalyzeMethod::Chunks(arguments::ChunkMethod::Entropy(256, 16)), 4)));
+		assert_eq!(
+			arguments::parse_entropy_params(&v, 3),
+			Err("--step needs --window".to_string()));
+		assert_eq!(
+			arguments::parse_entropy_params(&v, 5),
+			Ok((arguments::AnalyzeMethod::Chunks(arguments::ChunkMethod::Entropy(0, 0)), 0)));
+		let window_v = vec_str_conv(vec!["--window", "64"]);
+		assert_eq!(
+			arguments::parse_entropy_params(&window_v, 0),
+			Ok((arguments::AnalyzeMethod::Chunks(arguments::ChunkMethod::Entropy(64, 64)), 2)));
+		let err_v = vec_str_conv(vec!["--window", "b"]);
+		assert_eq!(
+			arguments::parse_entropy_params(&err_v, 0),
+			Err("--window is invalid".to_string()));
+		let err_step_v = vec_str_conv(vec!["--step", "0"]);
+		assert_eq!(
+			arguments::parse_entropy_params(&err_step_v, 0),
+			Err("--step is invalid".to_string()));
+	}
+
+	#[test]
+	fn parse_caesar_params() {
+		let v = vec_str_conv(vec!["a", "--top", "3", "--alphabet", "bytes", "f"]);
+		assert_eq!(
+			arguments::parse_caesar_params(&v, 1),
+			Ok((arguments::AnalyzeMethod::Whole(arguments::WholeMethod::Caesar(Alphabet::Bytes, 3)), 4)));
+		assert_eq!(
+			arguments::parse_caesar_params(&v, 5),
+			Ok((arguments::AnalyzeMethod::Whole(arguments::WholeMethod::Caesar(Alphabet::Letters, 5)), 0)));
+		let err_v = vec_str_conv(vec!["--alphabet", "runes"]);
+		assert_eq!(
+			arguments::parse_caesar_params(&err_v, 0),
+			Err("--alphabet is invalid".to_string()));
+	}
+
+	#[test]
+	fn parse_frequency_analysis_params() {
+		let v = vec_str_conv(vec!["a", "--non-overlapping", "--ngram", "2", "f"]);
+		assert_eq!(
+			arguments::parse_frequency_analysis_params(&v, 1),
+			Ok((arguments::AnalyzeMethod::Whole(
+				arguments::WholeMethod::NgramFrequencyAnalysis(2, false)), 3)));
+		assert_eq!(
+			arguments::parse_frequency_analysis_params(&v, 4),
+			Ok((arguments::AnalyzeMethod::Chunks(arguments::ChunkMethod::FrequencyAnalysis), 0)));
+		let err_v = vec_str_conv(vec!["--ngram", "two"]);
+		assert_eq!(
+			arguments::parse_frequency_analysis_params(&err_v, 0),
+			Err("--ngram is invalid".to_string()));
+		let err_v = vec_str_conv(vec!["--ngram", "0", "f"]);
+		assert_eq!(
+			arguments::parse_frequency_analysis_params(&err_v, 0),
+			Err("--ngram is invalid".to_string()));
+	}
+
+	#[test]
+	fn parse_vigenere_params() {
+		let v = vec_str_conv(vec![
+			"a", "--key-length", "7", "--alphabet", "bytes", "--max-period", "9", "f"]);
+		assert_eq!(
+			arguments::parse_vigenere_params(&v, 1),
+			Ok((arguments::AnalyzeMethod::Whole(
+				arguments::WholeMethod::Vigenere(Alphabet::Bytes, 9, 7)), 6)));
+		assert_eq!(
+			arguments::parse_vigenere_params(&v, 7),
+			Ok((arguments::AnalyzeMethod::Whole(
+				arguments::WholeMethod::Vigenere(Alphabet::Letters, 20, 0)), 0)));
+		let err_v = vec_str_conv(vec!["--key-length", "x"]);
+		assert_eq!(
+			arguments::parse_vigenere_params(&err_v, 0),
+			Err("--key-length is invalid".to_string()));
+	}
+
+	#[test]
+	fn parse_xor_repeating_params() {
+		let v = vec_str_conv(vec!["a", "--key-length", "3", "--max-period", "40", "f"]);
+		assert_eq!(
+			arguments::parse_xor_repeating_params(&v, 1),
+			Ok((arguments::AnalyzeMethod::Whole(arguments::WholeMethod::XorRepeating(40, 3)), 4)));
+		assert_eq!(
+			arguments::parse_xor_repeating_params(&v, 5),
+			Ok((arguments::AnalyzeMethod::Whole(arguments::WholeMethod::XorRepeating(20, 0)), 0)));
+		let err_v = vec_str_conv(vec!["--max-period", "-1"]);
+		assert_eq!(
+			arguments::parse_xor_repeating_params(&err_v, 0),
+			Err("--max-period is invalid".to_string()));
+	}
+
+	#[test]
+	fn parse_xor_single_params() {
+		let v = vec_str_conv(vec!["a", "--top", "10", "f"]);
+		assert_eq!(
+			arguments::parse_xor_single_params(&v, 1),
+			Ok((arguments::AnalyzeMethod::Whole(arguments::WholeMethod::XorSingle(10)), 2)));
+		assert_eq!(
+			arguments::parse_xor_single_params(&v, 3),
+			Ok((arguments::AnalyzeMethod::Whole(arguments::WholeMethod::XorSingle(5)), 0)));
+		let err_v = vec_str_conv(vec!["--top", "many"]);
+		assert_eq!(
+			arguments::parse_xor_single_params(&err_v, 0),
+			Err("--top is invalid".to_string()));
+	}
+
+	#[test]
+	fn parse_substitution_params() {
+		let v = vec_str_conv(vec!["a", "--restarts", "3", "f"]);
+		assert_eq!(
+			arguments::parse_substitution_params(&v, 1),
+			Ok((arguments::AnalyzeMethod::Whole(arguments::WholeMethod::Substitution(3)), 2)));
+		assert_eq!(
+			arguments::parse_substitution_params(&v, 3),
+			Ok((arguments::AnalyzeMethod::Whole(arguments::WholeMethod::Substitution(20)), 0)));
+		let err_v = vec_str_conv(vec!["--restarts", "x"]);
+		assert_eq!(
+			arguments::parse_substitution_params(&err_v, 0),
+			Err("--restarts is invalid".to_string()));
+	}
+
+	#[test]
+	fn parse_transposition_params() {
+		let v = vec_str_conv(vec!["a", "--max-period", "8", "--top", "2", "f"]);
+		assert_eq!(
+			arguments::parse_transposition_params(&v, 1),
+			Ok((arguments::AnalyzeMethod::Whole(arguments::WholeMethod::Transposition(8, 0, 2)), 4)));
+		let v = vec_str_conv(vec!["--key-length", "6", "f"]);
+		assert_eq!(
+			arguments::parse_transposition_params(&v, 0),
+			Ok((arguments::AnalyzeMethod::Whole(arguments::WholeMethod::Transposition(10, 6, 5)), 2)));
+		let err_v = vec_str_conv(vec!["--top", "x"]);
+		assert_eq!(
+			arguments::parse_transposition_params(&err_v, 0),
+			Err("--top is invalid".to_string()));
+		let err_v = vec_str_conv(vec!["--max-period", "1"]);
+		assert_eq!(
+			arguments::parse_transposition_params(&err_v, 0),
+			Err("--max-period is invalid".to_string()));
+	}
+
+	#[test]
+	fn parse_rail_fence_params() {
+		let v = vec_str_conv(vec!["a", "--top", "3", "--max-rails", "6", "f"]);
+		assert_eq!(
+			arguments::parse_rail_fence_params(&v, 1),
+			Ok((arguments::AnalyzeMethod::Whole(arguments::WholeMethod::RailFence(6, 3)), 4)));
+		assert_eq!(
+			arguments::parse_rail_fence_params(&v, 5),
+			Ok((arguments::AnalyzeMethod::Whole(arguments::WholeMethod::RailFence(10, 5)), 0)));
+		let err_v = vec_str_conv(vec!["--max-rails", "-3"]);
+		assert_eq!(
+			arguments::parse_rail_fence_params(&err_v, 0),
+			Err("--max-rails is invalid".to_string()));
+	}
+
+	#[test]
+	fn parse_train_params() {
+		let v = vec_str_conv(vec!["a", "--save", "m.bin", "--name", "logs", "--alphabet", "bytes", "f"]);
+		let method = arguments::WholeMethod::Train(
+			"m.bin".to_string(), "logs".to_string(), Alphabet::Bytes);
+		assert_eq!(
+			arguments::parse_train_params(&v, 1),
+			Ok((arguments::AnalyzeMethod::Whole(method), 6)));
+		let v = vec_str_conv(vec!["--save", "m.bin", "f"]);
+		let method = arguments::WholeMethod::Train(
+			"m.bin".to_string(), "custom".to_string(), Alphabet::Letters);
+		assert_eq!(
+			arguments::parse_train_params(&v, 0),
+			Ok((arguments::AnalyzeMethod::Whole(method), 2)));
+		assert_eq!(
+			arguments::parse_train_params(&vec_str_conv(vec!["f"]), 0),
+			Err("train needs --save FILE".to_string()));
+		assert_eq!(
+			arguments::parse_train_params(&vec_str_conv(vec!["--save"]), 0),
+			Err("--save is invalid".to_string()));
+		assert_eq!(
+			arguments::parse_train_params(&vec_str_conv(vec!["--alphabet", "words"]), 0),
+			Err("--alphabet is invalid".to_string()));
+	}
+
+	#[test]
+	fn parse_optionals_language_model() {
+		assert_eq!(
+			arguments::parse_optionals(&vec_str_conv(vec![
+				"", "--language-model", "m.bin", "score"])),
+			Ok((arguments::Sizes::U8, 0, 1, 0, MmapMode::Auto, InputFormat::Raw, Endian::Big,
+			    OutputFormat::Text, Some("m.bin".to_string()), 3)));
+		assert_eq!(
+			arguments::parse_optionals(&vec_str_conv(vec!["", "--language-model"])),
+			Err("--language-model is invalid".to_string()));
+	}
+
+	#[test]
+	fn parse_optionals_length() {
+		assert_eq!(
+			arguments::parse_optionals(&vec_str_conv(vec![
+				"", "--skip-first", "16", "--length", "32", "xor_single"])),
+			Ok((arguments::Sizes::U8, 16, 1, 32, MmapMode::Auto, InputFormat::Raw, Endian::Big,
+			    OutputFormat::Text, None, 5)));
+		assert_eq!(
+			arguments::parse_optionals(&vec_str_conv(vec!["", "--length", "-2"])),
+			Err("--length is invalid".to_string()));
+	}
+
+	#[test]
+	fn parse_optionals_mmap() {
+		assert_eq!(
+			arguments::parse_optionals(&vec_str_conv(vec![
+				"", "--mmap", "--bytes", "2", "kasiski_examination"])),
+			Ok((arguments::Sizes::U16, 0, 1, 0, MmapMode::Always, InputFormat::Raw, Endian::Big,
+			    OutputFormat::Text, None, 4)));
+	}
+
+	#[test]
+	fn parse_optionals_no_mmap() {
+		assert_eq!(
+			arguments::parse_optionals(&vec_str_conv(vec![
+				"", "--mmap", "--no-mmap", "min_max"])),
+			Ok((arguments::Sizes::U8, 0, 1, 0, MmapMode::Never, InputFormat::Raw, Endian::Big,
+			    OutputFormat::Text, None, 3)));
+	}
+
+	#[test]
+	fn parse_args_mmap_errors() {
+		assert_eq!(
+			arguments::parse_args_with_stdin(
+				&vec_str_conv(vec!["", "--mmap", "--input-format", "hex", "min_max", "FILE"]),
+				false),
+			Err("--mmap needs --input-format raw".to_string()));
+		assert_eq!(
+			arguments::parse_args_with_stdin(
+				&vec_str_conv(vec!["", "--mmap", "min_max", "-"]), false),
+			Err("--mmap cannot map standard input".to_string()));
+		assert_eq!(
+			arguments::parse_args_with_stdin(&vec_str_conv(vec!["", "--mmap", "min_max"]), true),
+			Err("--mmap cannot map standard input".to_string()));
+	}
+
 	#[test]
 	fn parse_optionals_all() {
 		assert_eq!(
 			arguments::parse_optionals(&vec_str_conv(vec![
 				"", "--bytes", "8", "--skip-first", "3", "--keep-every", "2"])),
-			Ok((arguments::Sizes::U64, 3, 2, 7)));
+			Ok((arguments::Sizes::U64, 3, 2, 0, MmapMode::Auto, InputFormat::Raw, Endian::Big,
+			    OutputFormat::Text, None, 7)));
+	}
+
+	#[test]
+	fn parse_optionals_formats() {
+		assert_eq!(
+			arguments::parse_optionals(&vec_str_conv(vec![
+				"", "--input-format", "base64", "--endian", "little",
+				"--output", "json", "method"])),
+			Ok((arguments::Sizes::U8, 0, 1, 0, MmapMode::Auto, InputFormat::Base64, Endian::Little,
+			    OutputFormat::Json, None, 7)));
 	}
 
 	#[test]
@@ -301,7 +1347,8 @@ mod tests {
 		assert_eq!(
 			arguments::parse_optionals(&vec_str_conv(vec![
 				"", "--skip-first", "3", "method", "--keep-every", "2"])),
-			Ok((arguments::Sizes::U8, 3, 1, 3)));
+			Ok((arguments::Sizes::U8, 3, 1, 0, MmapMode::Auto, InputFormat::Raw, Endian::Big,
+			    OutputFormat::Text, None, 3)));
 	}
 
 	#[test]
@@ -309,7 +1356,48 @@ mod tests {
 		assert_eq!(
 			arguments::parse_optionals(&vec_str_conv(vec![
 				"", "method", "--skip-first", "3", "--keep-every", "2"])),
-			Ok((arguments::Sizes::U8, 0, 1, 1)));
+			Ok((arguments::Sizes::U8, 0, 1, 0, MmapMode::Auto, InputFormat::Raw, Endian::Big,
+			    OutputFormat::Text, None, 1)));
+	}
+
+	#[test]
+	fn parse_args_with_stdin() {
+		assert_eq!(
+			arguments::parse_args_with_stdin(
+				&vec_str_conv(vec!["", "min_max"]), false),
+			Err("No file specified".to_string()));
+		assert_eq!(
+			arguments::parse_args_with_stdin(
+				&vec_str_conv(vec!["", "min_max"]), true),
+			Ok(arguments::Action {
+				skip_first: 0,
+				keep_every: 1,
+				length: 0,
+				mmap: MmapMode::Auto,
+				method: arguments::AnalyzeMethod::Chunks(arguments::ChunkMethod::MinMax),
+				input_format: InputFormat::Raw,
+				endian: Endian::Big,
+				output: OutputFormat::Text,
+				language_model: None,
+				size: arguments::Sizes::U8,
+				filename: "-".to_string(),
+				help: false }));
+		assert_eq!(
+			arguments::parse_args_with_stdin(
+				&vec_str_conv(vec!["", "min_max", "-"]), false),
+			Ok(arguments::Action {
+				skip_first: 0,
+				keep_every: 1,
+				length: 0,
+				mmap: MmapMode::Auto,
+				method: arguments::AnalyzeMethod::Chunks(arguments::ChunkMethod::MinMax),
+				input_format: InputFormat::Raw,
+				endian: Endian::Big,
+				output: OutputFormat::Text,
+				language_model: None,
+				size: arguments::Sizes::U8,
+				filename: "-".to_string(),
+				help: false }));
 	}
 
 	#[test]
@@ -330,7 +1418,13 @@ mod tests {
 			Ok(arguments::Action {
 				skip_first: 0,
 				keep_every: 1,
-				method: arguments::AnalyzeMethod::MinMax,
+				length: 0,
+				mmap: MmapMode::Auto,
+				method: arguments::AnalyzeMethod::Chunks(arguments::ChunkMethod::MinMax),
+				input_format: InputFormat::Raw,
+				endian: Endian::Big,
+				output: OutputFormat::Text,
+				language_model: None,
 				size: arguments::Sizes::U8,
 				filename: "file".to_string(),
 				help: false }));
@@ -340,7 +1434,13 @@ mod tests {
 			Ok(arguments::Action {
 				skip_first: 0,
 				keep_every: 1,
-				method: arguments::AnalyzeMethod::FrequencyAnalysis,
+				length: 0,
+				mmap: MmapMode::Auto,
+				method: arguments::AnalyzeMethod::Chunks(arguments::ChunkMethod::FrequencyAnalysis),
+				input_format: InputFormat::Raw,
+				endian: Endian::Big,
+				output: OutputFormat::Text,
+				language_model: None,
 				size: arguments::Sizes::U16,
 				filename: "da".to_string(),
 				help: false }));
@@ -351,10 +1451,80 @@ mod tests {
 			Ok(arguments::Action {
 				skip_first: 0,
 				keep_every: 1,
-				method: arguments::AnalyzeMethod::KasiskiExamination(8),
+				length: 0,
+				mmap: MmapMode::Auto,
+				method: arguments::AnalyzeMethod::Whole(arguments::WholeMethod::KasiskiExamination(8)),
+				input_format: InputFormat::Raw,
+				endian: Endian::Big,
+				output: OutputFormat::Text,
+				language_model: None,
 				size: arguments::Sizes::U32,
 				filename: "input".to_string(),
 				help: false }));
+		assert_eq!(
+			arguments::parse_args(&vec_str_conv(
+				vec!["", "--bytes", "1", "index_of_coincidence", "ct"])),
+			Ok(arguments::Action {
+				skip_first: 0,
+				keep_every: 1,
+				length: 0,
+				mmap: MmapMode::Auto,
+				method: arguments::AnalyzeMethod::Whole(arguments::WholeMethod::IndexOfCoincidence),
+				input_format: InputFormat::Raw,
+				endian: Endian::Big,
+				output: OutputFormat::Text,
+				language_model: None,
+				size: arguments::Sizes::U8,
+				filename: "ct".to_string(),
+				help: false }));
+		assert_eq!(
+			arguments::parse_args(&vec_str_conv(
+				vec!["", "chi_squared", "ct"])),
+			Ok(arguments::Action {
+				skip_first: 0,
+				keep_every: 1,
+				length: 0,
+				mmap: MmapMode::Auto,
+				method: arguments::AnalyzeMethod::Whole(arguments::WholeMethod::ChiSquared),
+				input_format: InputFormat::Raw,
+				endian: Endian::Big,
+				output: OutputFormat::Text,
+				language_model: None,
+				size: arguments::Sizes::U8,
+				filename: "ct".to_string(),
+				help: false }));
+		assert_eq!(
+			arguments::parse_args(&vec_str_conv(
+				vec!["", "score", "ct"])),
+			Ok(arguments::Action {
+				skip_first: 0,
+				keep_every: 1,
+				length: 0,
+				mmap: MmapMode::Auto,
+				method: arguments::AnalyzeMethod::Whole(arguments::WholeMethod::Score),
+				input_format: InputFormat::Raw,
+				endian: Endian::Big,
+				output: OutputFormat::Text,
+				language_model: None,
+				size: arguments::Sizes::U8,
+				filename: "ct".to_string(),
+				help: false }));
+		assert_eq!(
+			arguments::parse_args(&vec_str_conv(
+				vec!["", "period_sweep", "--max-period", "30", "ct"])),
+			Ok(arguments::Action {
+				skip_first: 0,
+				keep_every: 1,
+				length: 0,
+				mmap: MmapMode::Auto,
+				method: arguments::AnalyzeMethod::Whole(arguments::WholeMethod::PeriodSweep(30)),
+				input_format: InputFormat::Raw,
+				endian: Endian::Big,
+				output: OutputFormat::Text,
+				language_model: None,
+				size: arguments::Sizes::U8,
+				filename: "ct".to_string(),
+				help: false }));
 		assert_eq!(
 			arguments::parse_args(&vec_str_conv(
 				vec!["", "--bytes", "8", "--keep-every", "4",
@@ -362,7 +1532,13 @@ mod tests {
 			Ok(arguments::Action {
 				skip_first: 2,
 				keep_every: 4,
-				method: arguments::AnalyzeMethod::KasiskiExamination(5),
+				length: 0,
+				mmap: MmapMode::Auto,
+				method: arguments::AnalyzeMethod::Whole(arguments::WholeMethod::KasiskiExamination(5)),
+				input_format: InputFormat::Raw,
+				endian: Endian::Big,
+				output: OutputFormat::Text,
+				language_model: None,
 				size: arguments::Sizes::U64,
 				filename: "in".to_string(),
 				help: false }));
@@ -372,7 +1548,13 @@ mod tests {
 			Ok(arguments::Action {
 				skip_first: 0,
 				keep_every: 1,
-				method: arguments::AnalyzeMethod::KasiskiExamination(5),
+				length: 0,
+				mmap: MmapMode::Auto,
+				method: arguments::AnalyzeMethod::Whole(arguments::WholeMethod::KasiskiExamination(5)),
+				input_format: InputFormat::Raw,
+				endian: Endian::Big,
+				output: OutputFormat::Text,
+				language_model: None,
 				size: arguments::Sizes::U128,
 				filename: "".to_string(),
 				help: true }));
@@ -382,7 +1564,13 @@ mod tests {
 			Ok(arguments::Action {
 				skip_first: 0,
 				keep_every: 1,
+				length: 0,
+				mmap: MmapMode::Auto,
 				method: arguments::AnalyzeMethod::None,
+				input_format: InputFormat::Raw,
+				endian: Endian::Big,
+				output: OutputFormat::Text,
+				language_model: None,
 				size: arguments::Sizes::U8,
 				filename: "".to_string(),
 				help: true }));
@@ -1,27 +1,236 @@
 use std::io;
+use std::borrow::Cow;
+use std::convert::TryFrom;
 use std::fs::File;
 use std::io::Read;
+use std::marker::PhantomData;
 use std::ops::Shl;
 use std::ops::AddAssign;
 use super::types::NumBytes;
 
 
-pub fn read_file(filename: &str) -> Result<Vec<u8>, io::Error> {
-	let mut file = File::open(filename)?;
+/// Filename to read from standard input
+pub const STDIN_FILENAME: &str = "-";
+
+fn read_to_vec<R: Read>(reader: &mut R) -> Result<Vec<u8>, io::Error> {
 	let mut vec: Vec<u8> = Vec::new();
-	file.read_to_end(&mut vec)?;
+	reader.read_to_end(&mut vec)?;
 	Ok(vec)
 }
 
+/// Bytes which are read at once when streaming, a multiple of every word size
+const CHUNK_SIZE: usize = 1 << 16;
+
+/// Raw input files of at least this size are memory mapped by MmapMode::Auto
+pub const MMAP_THRESHOLD: u64 = 1 << 26;
+
+/// Open the file for reading, STDIN_FILENAME reads from standard input
+pub fn open_file(filename: &str) -> Result<Box<dyn Read>, io::Error> {
+	if filename == STDIN_FILENAME {
+		return Ok(Box::new(io::stdin().lock()));
+	}
+	return Ok(Box::new(File::open(filename)?));
+}
+
+/// Read the whole file, STDIN_FILENAME reads from standard input
+pub fn read_file(filename: &str) -> Result<Vec<u8>, io::Error> {
+	return read_to_vec(&mut open_file(filename)?);
+}
+
+/// Encoding of the input file
+#[derive(PartialEq, Debug)]
+pub enum InputFormat {
+	Raw,
+	Hex,
+	Base64,
+	Base32,
+}
+
+fn hex_value(c: u8) -> Option<u8> {
+	return match c {
+		b'0'..=b'9' => Some(c - b'0'),
+		b'a'..=b'f' => Some(c - b'a' + 10),
+		b'A'..=b'F' => Some(c - b'A' + 10),
+		_ => None,
+	};
+}
+
+// Standard and URL-safe alphabet
+fn base64_value(c: u8) -> Option<u8> {
+	return match c {
+		b'A'..=b'Z' => Some(c - b'A'),
+		b'a'..=b'z' => Some(c - b'a' + 26),
+		b'0'..=b'9' => Some(c - b'0' + 52),
+		b'+' | b'-' => Some(62),
+		b'/' | b'_' => Some(63),
+		_ => None,
+	};
+}
+
+fn base32_value(c: u8) -> Option<u8> {
+	return match c {
+		b'A'..=b'Z' => Some(c - b'A'),
+		b'a'..=b'z' => Some(c - b'a'),
+		b'2'..=b'7' => Some(c - b'2' + 26),
+		_ => None,
+	};
+}
+
+// Decoder of text where every character holds bits_per_char bits.
+// Whitespace and, if the format has it, trailing padding is ignored, left
+// over bits are dropped. The text can be decoded in pieces.
+struct BaseDecoder {
+	bits_per_char: u32,
+	value: fn(u8) -> Option<u8>,
+	has_padding: bool,
+	// Whether the characters need to come in pairs, like hex digits
+	pairs: bool,
+	name: &'static str,
+	buffer: u32,
+	buffer_bits: u32,
+	padding: bool,
+	chars: usize,
+}
+
+impl BaseDecoder {
+	// Decoder of the format, raw input needs none
+	fn new(format: &InputFormat) -> Option<BaseDecoder> {
+		let (bits_per_char, value, has_padding, pairs, name): (_, fn(u8) -> Option<u8>, _, _, _) =
+			match format {
+				InputFormat::Raw => return None,
+				InputFormat::Hex => (4, hex_value, false, true, "hex"),
+				InputFormat::Base64 => (6, base64_value, true, false, "base64"),
+				InputFormat::Base32 => (5, base32_value, true, false, "base32"),
+			};
+		return Some(BaseDecoder {
+			bits_per_char: bits_per_char,
+			value: value,
+			has_padding: has_padding,
+			pairs: pairs,
+			name: name,
+			buffer: 0,
+			buffer_bits: 0,
+			padding: false,
+			chars: 0,
+		});
+	}
+
+	// Append the bytes of the next piece of text to result
+	fn decode(&mut self, text: &[u8], result: &mut Vec<u8>) -> Result<(), io::Error> {
+		result.reserve(text.len() * self.bits_per_char as usize / 8);
+		for c in text {
+			if c.is_ascii_whitespace() {
+				continue;
+			}
+			if self.has_padding && *c == b'=' {
+				self.padding = true;
+				continue;
+			}
+			let v = match (self.value)(*c) {
+				Some(v) if !self.padding => v,
+				_ => {
+					let err = format!("Invalid {} input character: {:?}", self.name, *c as char);
+					return Err(io::Error::other(err));
+				},
+			};
+			self.chars += 1;
+			self.buffer = (self.buffer << self.bits_per_char) | u32::from(v);
+			self.buffer_bits += self.bits_per_char;
+			if self.buffer_bits >= 8 {
+				self.buffer_bits -= 8;
+				result.push((self.buffer >> self.buffer_bits) as u8);
+				self.buffer &= (1 << self.buffer_bits) - 1;
+			}
+		}
+		return Ok(());
+	}
+
+	// Check the end of the text
+	fn finish(&self) -> Result<(), io::Error> {
+		if self.pairs && self.chars % 2 != 0 {
+			return Err(io::Error::other("Hex input needs an even number of digits"));
+		}
+		return Ok(());
+	}
+}
+
+/// Decode text encoded input to the raw bytes
+pub fn decode_input(vec: Vec<u8>, format: &InputFormat) -> Result<Vec<u8>, io::Error> {
+	let mut decoder = match BaseDecoder::new(format) {
+		Some(d) => d,
+		None => return Ok(vec),
+	};
+	let mut result: Vec<u8> = Vec::new();
+	decoder.decode(&vec, &mut result)?;
+	decoder.finish()?;
+	return Ok(result);
+}
+
+// Reader which decodes the text of another reader chunk by chunk
+struct DecodeReader<R> {
+	reader: R,
+	decoder: BaseDecoder,
+	text: Vec<u8>,
+	decoded: Vec<u8>,
+	// Position of the next read in decoded
+	pos: usize,
+	done: bool,
+}
+
+impl<R: Read> DecodeReader<R> {
+	fn new(reader: R, decoder: BaseDecoder) -> DecodeReader<R> {
+		return DecodeReader {
+			reader: reader,
+			decoder: decoder,
+			text: vec![0; CHUNK_SIZE],
+			decoded: Vec::new(),
+			pos: 0,
+			done: false,
+		};
+	}
+}
+
+impl<R: Read> Read for DecodeReader<R> {
+	fn read(&mut self, buf: &mut [u8]) -> Result<usize, io::Error> {
+		while self.pos == self.decoded.len() {
+			if self.done {
+				return Ok(0);
+			}
+			self.decoded.clear();
+			self.pos = 0;
+			let len = read_chunk(&mut self.reader, &mut self.text)?;
+			if len == 0 {
+				self.done = true;
+				self.decoder.finish()?;
+			}
+			self.decoder.decode(&self.text[..len], &mut self.decoded)?;
+		}
+		let n = std::cmp::min(buf.len(), self.decoded.len() - self.pos);
+		buf[..n].copy_from_slice(&self.decoded[self.pos..self.pos + n]);
+		self.pos += n;
+		return Ok(n);
+	}
+}
+
+/// Byte order of the words of the input
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Endian {
+	Big,
+	Little,
+}
+
+/// Group the bytes into words of T, fails if the length of the input is no
+/// multiple of the size of T
 pub fn convert_vec<T: Copy + NumBytes + AddAssign + Shl + From<u8> +
-               From<<T as Shl>::Output>>(vec: &Vec<u8>) -> Result<Vec<T>, io::Error> {
+               From<<T as Shl>::Output>>(vec: &[u8], endian: &Endian)
+		-> Result<Vec<T>, io::Error> {
 	let num_bytes = T::BYTES;
 	let num_bytes_usize = usize::from(num_bytes);
 	if vec.len() % num_bytes_usize != 0 {
 		let err = format!(
 			"Vector length needs to be a multiple of T's size ({} bytes)",
 			T::BYTES);
-		return Err(io::Error::new(io::ErrorKind::Other, err));
+		return Err(io::Error::other(err));
 	}
 	let new_len: usize = vec.len() / num_bytes_usize;
 	let mut conv: Vec<T> = Vec::with_capacity(new_len);
@@ -30,7 +239,10 @@ pub fn convert_vec<T: Copy + NumBytes + AddAssign + Shl + From<u8> +
 	for i in 0..new_len {
 		let mut val: T = T::from(0);
 		for j in 0..num_bytes {
-			let k: T = T::from(num_bytes - j - 1);
+			let k: T = match endian {
+				Endian::Big => T::from(num_bytes - j - 1),
+				Endian::Little => T::from(j),
+			};
 			let shl: T = T::from(k << t_three); // * 8
 			let pos = i * num_bytes_usize + usize::from(j);
 			val += T::from(T::from(vec[pos]) << shl);
@@ -40,31 +252,344 @@ pub fn convert_vec<T: Copy + NumBytes + AddAssign + Shl + From<u8> +
 	Ok(conv)
 }
 
-pub fn filter_input_vec<T: Copy>(vec: &Vec<T>, keep_every: usize, skip_first: usize)
+/// Split the words into their bytes, the reverse of convert_vec
+pub fn words_to_bytes<T: Copy + NumBytes + Into<u128>>(vec: &[T], endian: &Endian) -> Vec<u8> {
+	let num_bytes = u32::from(T::BYTES);
+	let mut result: Vec<u8> = Vec::with_capacity(vec.len() * (num_bytes as usize));
+	for w in vec {
+		let value: u128 = (*w).into();
+		for j in 0..num_bytes {
+			let k = match endian {
+				Endian::Big => num_bytes - j - 1,
+				Endian::Little => j,
+			};
+			result.push((value >> (8 * k)) as u8);
+		}
+	}
+	return result;
+}
+
+/// Skip the first skip_first words and keep only every keep_every-th word of
+/// the rest
+pub fn filter_input_vec<T: Copy>(vec: &[T], keep_every: usize, skip_first: usize)
 		-> Result<Vec<T>, io::Error> {
 	if keep_every == 0 {
 		let err = "Cannot keep every 0.th element, parameter needs to be > 0";
-		return Err(io::Error::new(io::ErrorKind::Other, err));
+		return Err(io::Error::other(err));
 	}
 	let mut result: Vec<T> = Vec::with_capacity(vec.len() % keep_every);
-	for i in skip_first..vec.len() {
+	for (i, v) in vec.iter().enumerate().skip(skip_first) {
 		if (i - skip_first) % keep_every == 0 {
-			result.push(vec[i]);
+			result.push(*v);
 		}
 	}
 	return Ok(result);
 }
 
+// Fill the buffer, less bytes are only read at the end of the input
+fn read_chunk<R: Read>(reader: &mut R, buf: &mut [u8]) -> Result<usize, io::Error> {
+	let mut len = 0;
+	while len < buf.len() {
+		match reader.read(&mut buf[len..]) {
+			Ok(0) => break,
+			Ok(n) => len += n,
+			Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+			Err(e) => return Err(e),
+		};
+	}
+	return Ok(len);
+}
+
+// The crate has no dependencies, so mmap and munmap of the C library are
+// declared here instead of pulling in a crate like libc. MappedFile is the
+// only user of this foreign code.
+#[cfg(unix)]
+mod ffi {
+	pub use std::os::raw::c_int;
+	pub use std::os::raw::c_long;
+	pub use std::os::raw::c_void;
+
+	pub const PROT_READ: c_int = 1;
+	pub const MAP_PRIVATE: c_int = 2;
+
+	extern "C" {
+		pub fn mmap(
+			addr: *mut c_void, len: usize, prot: c_int, flags: c_int, fd: c_int,
+			offset: c_long) -> *mut c_void;
+		pub fn munmap(addr: *mut c_void, len: usize) -> c_int;
+	}
+}
+
+/// Read-only memory mapping of a whole file. The bytes are paged in from
+/// the file when they are accessed instead of being copied to the heap.
+/// Truncating the file while it is mapped crashes the process.
+pub struct MappedFile {
+	ptr: *const u8,
+	len: usize,
+}
+
+impl MappedFile {
+	#[cfg(unix)]
+	pub fn open(filename: &str) -> Result<MappedFile, io::Error> {
+		use std::os::unix::io::AsRawFd;
+		let file = File::open(filename)?;
+		let len = match usize::try_from(file.metadata()?.len()) {
+			Ok(l) => l,
+			Err(_) => return Err(io::Error::other("File is too large to be mapped")),
+		};
+		// Empty mappings are not allowed
+		if len == 0 {
+			return Ok(MappedFile { ptr: std::ptr::NonNull::dangling().as_ptr(), len: 0 });
+		}
+		// The mapping stays valid after the file is closed
+		let ptr = unsafe {
+			ffi::mmap(
+				std::ptr::null_mut(), len, ffi::PROT_READ, ffi::MAP_PRIVATE,
+				file.as_raw_fd(), 0)
+		};
+		// MAP_FAILED
+		if ptr as isize == -1 {
+			return Err(io::Error::last_os_error());
+		}
+		return Ok(MappedFile { ptr: ptr as *const u8, len: len });
+	}
+
+	#[cfg(not(unix))]
+	pub fn open(_filename: &str) -> Result<MappedFile, io::Error> {
+		return Err(io::Error::new(
+			io::ErrorKind::Unsupported,
+			"Memory mapped input is not supported on this platform"));
+	}
+}
+
+impl std::ops::Deref for MappedFile {
+	type Target = [u8];
+
+	fn deref(&self) -> &[u8] {
+		// ptr points to len readable bytes until the mapping is dropped
+		return unsafe { std::slice::from_raw_parts(self.ptr, self.len) };
+	}
+}
+
+impl Drop for MappedFile {
+	fn drop(&mut self) {
+		#[cfg(unix)]
+		if self.len > 0 {
+			unsafe {
+				ffi::munmap(self.ptr as *mut ffi::c_void, self.len);
+			}
+		}
+	}
+}
+
+/// When raw input files are memory mapped
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum MmapMode {
+	// Files of at least MMAP_THRESHOLD bytes
+	Auto,
+	Always,
+	Never,
+}
+
+fn use_mmap(filename: &str, mmap: MmapMode) -> Result<bool, io::Error> {
+	return match mmap {
+		MmapMode::Always => Ok(true),
+		MmapMode::Never => Ok(false),
+		MmapMode::Auto if filename == STDIN_FILENAME || !cfg!(unix) => Ok(false),
+		MmapMode::Auto => Ok(std::fs::metadata(filename)?.len() >= MMAP_THRESHOLD),
+	};
+}
+
+/// Opened input of open_input
+pub enum Input {
+	/// Memory mapped raw file
+	Mapped(MappedFile),
+	/// File or standard input which is read and decoded while iterating
+	Stream(Box<dyn Read>),
+}
+
+impl Input {
+	/// Reader over the bytes of the input
+	pub fn reader(&mut self) -> Box<dyn Read + '_> {
+		return match self {
+			Input::Mapped(mapped) => Box::new(&mapped[..]),
+			Input::Stream(reader) => Box::new(reader),
+		};
+	}
+
+	/// All words of the input, grouped, filtered and limited like
+	/// WordChunks. Single byte words of a mapped file are borrowed from the
+	/// mapping instead of being copied, unless only every keep_every-th word
+	/// is kept.
+	pub fn words<
+		T: Copy + NumBytes + AddAssign + Shl + From<u8> + From<<T as Shl>::Output>
+	>(&mut self, endian: Endian, keep_every: usize, skip_first: usize, length: usize)
+			-> Result<Cow<'_, [T]>, io::Error> {
+		let reader: Box<dyn Read + '_> = match self {
+			Input::Mapped(mapped) => {
+				match T::from_bytes(&mapped[..]) {
+					Some(words) if keep_every == 1 => {
+						let start = std::cmp::min(skip_first, words.len());
+						let end = match length {
+							0 => words.len(),
+							l => std::cmp::min(start.saturating_add(l), words.len()),
+						};
+						return Ok(Cow::Borrowed(&words[start..end]));
+					},
+					_ => Box::new(&mapped[..]),
+				}
+			},
+			Input::Stream(reader) => Box::new(reader),
+		};
+		let chunks = WordChunks::<T, _>::new(reader, endian, keep_every, skip_first, length)?;
+		return Ok(Cow::Owned(chunks.collect_words()?));
+	}
+}
+
+/// Open the file, which is read and decoded while iterating over its words.
+/// Raw input is memory mapped depending on mmap, MmapMode::Auto maps files
+/// of at least MMAP_THRESHOLD bytes. Only raw files can be mapped.
+pub fn open_input(filename: &str, format: &InputFormat, mmap: MmapMode)
+		-> Result<Input, io::Error> {
+	if mmap == MmapMode::Always && *format != InputFormat::Raw {
+		return Err(io::Error::other("Only raw input can be memory mapped"));
+	}
+	if mmap == MmapMode::Always && filename == STDIN_FILENAME {
+		return Err(io::Error::other("Standard input cannot be memory mapped"));
+	}
+	if *format == InputFormat::Raw && use_mmap(filename, mmap)? {
+		return Ok(Input::Mapped(MappedFile::open(filename)?));
+	}
+	return match BaseDecoder::new(format) {
+		Some(decoder) => Ok(Input::Stream(Box::new(DecodeReader::new(open_file(filename)?, decoder)))),
+		None => Ok(Input::Stream(open_file(filename)?)),
+	};
+}
+
+/// Iterator over the words of a reader in chunks, without holding the whole
+/// input in memory. Every chunk is grouped into words of T like convert_vec,
+/// filtered like filter_input_vec and limited like limit_input_vec.
+pub struct WordChunks<T, R> {
+	reader: R,
+	endian: Endian,
+	keep_every: usize,
+	skip_first: usize,
+	length: usize,
+	// Index of the next word and number of kept words
+	index: usize,
+	kept: usize,
+	buf: Vec<u8>,
+	done: bool,
+	word: PhantomData<T>,
+}
+
+impl<
+	T: Copy + NumBytes + AddAssign + Shl + From<u8> + From<<T as Shl>::Output>,
+	R: Read
+> WordChunks<T, R> {
+	pub fn new(reader: R, endian: Endian, keep_every: usize, skip_first: usize, length: usize)
+			-> Result<WordChunks<T, R>, io::Error> {
+		if keep_every == 0 {
+			let err = "Cannot keep every 0.th element, parameter needs to be > 0";
+			return Err(io::Error::other(err));
+		}
+		return Ok(WordChunks {
+			reader: reader,
+			endian: endian,
+			keep_every: keep_every,
+			skip_first: skip_first,
+			length: length,
+			index: 0,
+			kept: 0,
+			buf: vec![0; CHUNK_SIZE],
+			done: false,
+			word: PhantomData,
+		});
+	}
+
+	/// Read all remaining words into one vector
+	pub fn collect_words(self) -> Result<Vec<T>, io::Error> {
+		let mut result: Vec<T> = Vec::new();
+		for chunk in self {
+			result.extend(chunk?);
+		}
+		return Ok(result);
+	}
+
+	fn next_chunk(&mut self) -> Result<Option<Vec<T>>, io::Error> {
+		let len = read_chunk(&mut self.reader, &mut self.buf)?;
+		if len == 0 {
+			return Ok(None);
+		}
+		let words = convert_vec::<T>(&self.buf[..len], &self.endian)?;
+		let mut chunk: Vec<T> = Vec::with_capacity(words.len());
+		for w in words {
+			let i = self.index;
+			self.index += 1;
+			if i < self.skip_first || (i - self.skip_first) % self.keep_every != 0 {
+				continue;
+			}
+			chunk.push(w);
+			self.kept += 1;
+			if self.length > 0 && self.kept == self.length {
+				self.done = true;
+				break;
+			}
+		}
+		return Ok(Some(chunk));
+	}
+}
+
+impl<
+	T: Copy + NumBytes + AddAssign + Shl + From<u8> + From<<T as Shl>::Output>,
+	R: Read
+> Iterator for WordChunks<T, R> {
+	type Item = Result<Vec<T>, io::Error>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		if self.done {
+			return None;
+		}
+		let result = self.next_chunk();
+		if !matches!(result, Ok(Some(_))) {
+			self.done = true;
+		}
+		return result.transpose();
+	}
+}
+
+/// Keep only the first length words, a length of 0 keeps all words
+pub fn limit_input_vec<T>(mut vec: Vec<T>, length: usize) -> Vec<T> {
+	if length > 0 {
+		vec.truncate(length);
+	}
+	return vec;
+}
+
 
 #[cfg(test)]
 mod tests {
 	use std::io;
 	use crate::input::convert_vec;
+	use crate::input::words_to_bytes;
+	use crate::input::Endian;
 	use crate::input::read_file;
+	use crate::input::read_to_vec;
 	use crate::input::filter_input_vec;
+	use crate::input::limit_input_vec;
+	use crate::input::WordChunks;
+	use crate::input::open_input;
+	use crate::input::MappedFile;
+	use crate::input::MmapMode;
+	use crate::input::Input;
+	use crate::input::decode_input;
+	use crate::input::InputFormat;
+	use crate::input::BaseDecoder;
+	use crate::input::DecodeReader;
 	use std::ops::Shl;
 	use std::ops::AddAssign;
 	use std::fmt::Debug;
+	use std::borrow::Cow;
 	use crate::types::NumBytes;
 
 	#[test]
@@ -74,31 +599,55 @@ mod tests {
 			0x11, 0x22, 0x44, 0x88, 0x18, 0x24, 0x42, 0x00,
 			0x12, 0x34, 0x56, 0x78, 0x90, 0x55, 0x33, 0x99,
 			0x12, 0x34, 0x56, 0x78, 0x90, 0x55, 0x33, 0x99];
-		let vec8 = convert_vec::<u8>(&orig_vec)?;
+		let vec8 = convert_vec::<u8>(&orig_vec, &Endian::Big)?;
 		assert_eq!(orig_vec, vec8);
-		let vec16 = convert_vec::<u16>(&orig_vec)?;
+		let vec16 = convert_vec::<u16>(&orig_vec, &Endian::Big)?;
 		assert_eq!(vec16,
 			[0x0102, 0x0408, 0x1020, 0x4080,
 			 0x1122, 0x4488, 0x1824, 0x4200,
 			 0x1234, 0x5678, 0x9055, 0x3399,
 			 0x1234, 0x5678, 0x9055, 0x3399]);
 
-		let vec32 = convert_vec::<u32>(&orig_vec)?;
+		let vec32 = convert_vec::<u32>(&orig_vec, &Endian::Big)?;
 		assert_eq!(vec32,
 			[0x01020408, 0x10204080,
 			 0x11224488, 0x18244200,
 			 0x12345678, 0x90553399,
 			 0x12345678, 0x90553399]);
-		let vec64 = convert_vec::<u64>(&orig_vec)?;
+		let vec64 = convert_vec::<u64>(&orig_vec, &Endian::Big)?;
 		assert_eq!(vec64,
 			[0x0102040810204080,
 			 0x1122448818244200,
 			 0x1234567890553399,
 			 0x1234567890553399]);
-		let vec128 = convert_vec::<u128>(&orig_vec)?;
+		let vec128 = convert_vec::<u128>(&orig_vec, &Endian::Big)?;
 		assert_eq!(vec128,
 			[0x01020408102040801122448818244200,
 			 0x12345678905533991234567890553399]);
+		assert_eq!(words_to_bytes(&vec16, &Endian::Big), orig_vec);
+		assert_eq!(words_to_bytes(&vec128, &Endian::Big), orig_vec);
+		return Ok(());
+	}
+
+	#[test]
+	fn test_convert_vec_little_endian() -> Result<(), io::Error> {
+		let orig_vec: Vec<u8> = vec![
+			0x01, 0x02, 0x04, 0x08, 0x10, 0x20, 0x40, 0x80,
+			0x12, 0x34, 0x56, 0x78, 0x90, 0x55, 0x33, 0x99];
+		let vec8 = convert_vec::<u8>(&orig_vec, &Endian::Little)?;
+		assert_eq!(orig_vec, vec8);
+		let vec16 = convert_vec::<u16>(&orig_vec, &Endian::Little)?;
+		assert_eq!(vec16,
+			[0x0201, 0x0804, 0x2010, 0x8040,
+			 0x3412, 0x7856, 0x5590, 0x9933]);
+		let vec32 = convert_vec::<u32>(&orig_vec, &Endian::Little)?;
+		assert_eq!(vec32, [0x08040201, 0x80402010, 0x78563412, 0x99335590]);
+		let vec64 = convert_vec::<u64>(&orig_vec, &Endian::Little)?;
+		assert_eq!(vec64, [0x8040201008040201, 0x9933559078563412]);
+		let vec128 = convert_vec::<u128>(&orig_vec, &Endian::Little)?;
+		assert_eq!(vec128, [0x99335590785634128040201008040201]);
+		assert_eq!(words_to_bytes(&vec32, &Endian::Little), orig_vec);
+		assert_eq!(words_to_bytes(&vec128, &Endian::Little), orig_vec);
 		return Ok(());
 	}
 
@@ -122,7 +671,7 @@ mod tests {
                                  From<<T as Shl>::Output> + Debug>() {
 		for i in 1..T::BYTES {
 			let vec = create_test_vec(usize::from(T::BYTES + i));
-			check_convert_vec_error(convert_vec::<T>(&vec).unwrap_err(), T::BYTES);
+			check_convert_vec_error(convert_vec::<T>(&vec, &Endian::Big).unwrap_err(), T::BYTES);
 		}
 	}
 
@@ -143,6 +692,13 @@ mod tests {
 			[0xde, 0xad, 0xbe, 0xef, 0x00, 0x00, 0x99, 0x88, 0x55, 0x11, 0x33])
 	}
 
+	#[test]
+	fn test_read_to_vec() {
+		let data: Vec<u8> = vec![0xde, 0xad, 0xbe, 0xef];
+		let vec = read_to_vec(&mut &data[..]).unwrap();
+		assert_eq!(vec, data);
+	}
+
 	#[test]
 	fn test_read_file_errors() -> Result<(), ()> {
 		if let Some(result) = read_file("tests/files/inexistant").err() {
@@ -194,4 +750,189 @@ mod tests {
 			filter_input_vec(&vec, 3, 2).unwrap(),
 			vec![12, 53, 86]);
 	}
+
+	fn decode_str(input: &str, format: InputFormat) -> Result<Vec<u8>, io::Error> {
+		return decode_input(input.as_bytes().to_vec(), &format);
+	}
+
+	fn check_decode_error(result: Result<Vec<u8>, io::Error>, msg: &str) {
+		let err = result.unwrap_err();
+		assert_eq!(err.kind(), io::ErrorKind::Other);
+		assert_eq!(err.to_string(), msg);
+	}
+
+	#[test]
+	fn decode_input_raw() -> Result<(), io::Error> {
+		assert_eq!(decode_str("de ad", InputFormat::Raw)?, b"de ad");
+		return Ok(());
+	}
+
+	#[test]
+	fn decode_input_hex() -> Result<(), io::Error> {
+		assert_eq!(
+			decode_str("deadBEEF 00\n0099\n", InputFormat::Hex)?,
+			[0xde, 0xad, 0xbe, 0xef, 0x00, 0x00, 0x99]);
+		assert_eq!(decode_str("", InputFormat::Hex)?, []);
+		check_decode_error(
+			decode_str("dea", InputFormat::Hex),
+			"Hex input needs an even number of digits");
+		check_decode_error(
+			decode_str("dx", InputFormat::Hex),
+			"Invalid hex input character: 'x'");
+		check_decode_error(
+			decode_str("de==", InputFormat::Hex),
+			"Invalid hex input character: '='");
+		return Ok(());
+	}
+
+	#[test]
+	fn decode_input_base64() -> Result<(), io::Error> {
+		assert_eq!(decode_str("TWFu", InputFormat::Base64)?, b"Man");
+		assert_eq!(decode_str("TWE=", InputFormat::Base64)?, b"Ma");
+		assert_eq!(decode_str("TQ==\n", InputFormat::Base64)?, b"M");
+		assert_eq!(decode_str("TQ", InputFormat::Base64)?, b"M");
+		assert_eq!(
+			decode_str("3q2+7w==", InputFormat::Base64)?,
+			[0xde, 0xad, 0xbe, 0xef]);
+		assert_eq!(
+			decode_str("3q2-7w", InputFormat::Base64)?,
+			[0xde, 0xad, 0xbe, 0xef]);
+		check_decode_error(
+			decode_str("TQ==TQ==", InputFormat::Base64),
+			"Invalid base64 input character: 'T'");
+		check_decode_error(
+			decode_str("T*", InputFormat::Base64),
+			"Invalid base64 input character: '*'");
+		return Ok(());
+	}
+
+	#[test]
+	fn decode_input_base32() -> Result<(), io::Error> {
+		assert_eq!(decode_str("MZXW6YTBOI======", InputFormat::Base32)?, b"foobar");
+		assert_eq!(decode_str("mzxw6ytb", InputFormat::Base32)?, b"fooba");
+		assert_eq!(decode_str("MY======", InputFormat::Base32)?, b"f");
+		check_decode_error(
+			decode_str("MY1", InputFormat::Base32),
+			"Invalid base32 input character: '1'");
+		return Ok(());
+	}
+
+	#[test]
+	fn limit_input_vec_test() {
+		assert_eq!(limit_input_vec(vec![1_u8, 2, 3], 2), vec![1, 2]);
+		assert_eq!(limit_input_vec(vec![1_u8, 2, 3], 5), vec![1, 2, 3]);
+		assert_eq!(limit_input_vec(vec![1_u8, 2, 3], 0), vec![1, 2, 3]);
+	}
+
+	#[test]
+	fn word_chunks_filter() -> Result<(), io::Error> {
+		let data: Vec<u8> = (0..200_u16).map(|i| i as u8).collect();
+		let words = WordChunks::<u16, _>::new(&data[..], Endian::Little, 3, 2, 0)?
+			.collect_words()?;
+		let expected = filter_input_vec(&convert_vec(&data, &Endian::Little)?, 3, 2)?;
+		assert_eq!(words, expected);
+		let limited = WordChunks::<u16, _>::new(&data[..], Endian::Little, 3, 2, 4)?
+			.collect_words()?;
+		assert_eq!(limited, expected[..4]);
+		return Ok(());
+	}
+
+	#[test]
+	fn word_chunks_large() -> Result<(), io::Error> {
+		let data: Vec<u8> = (0..300000_u32).map(|i| (i % 251) as u8).collect();
+		let chunks: Vec<Vec<u8>> = WordChunks::new(&data[..], Endian::Big, 1, 0, 0)?
+			.collect::<Result<Vec<Vec<u8>>, io::Error>>()?;
+		assert_eq!(chunks.len(), 5);
+		assert_eq!(chunks.concat(), data);
+		return Ok(());
+	}
+
+	#[test]
+	fn word_chunks_errors() {
+		let data: Vec<u8> = vec![1, 2, 3];
+		let mut chunks = WordChunks::<u16, _>::new(&data[..], Endian::Big, 1, 0, 0).unwrap();
+		let err = chunks.next().unwrap().unwrap_err();
+		assert_eq!(err.to_string(), "Vector length needs to be a multiple of T's size (2 bytes)");
+		assert!(chunks.next().is_none());
+		let err = WordChunks::<u8, _>::new(&data[..], Endian::Big, 0, 0, 0).err().unwrap();
+		assert_eq!(
+			err.to_string(),
+			"Cannot keep every 0.th element, parameter needs to be > 0");
+	}
+
+	#[test]
+	fn open_input_decoded() -> Result<(), io::Error> {
+		let mut raw = open_input("tests/files/read_file", &InputFormat::Raw, MmapMode::Never)?;
+		let words = WordChunks::<u8, _>::new(raw.reader(), Endian::Big, 1, 0, 0)?;
+		assert_eq!(words.collect_words()?, read_file("tests/files/read_file")?);
+		let mut hex = open_input("tests/files/read_file", &InputFormat::Hex, MmapMode::Auto)?;
+		let err = hex.words::<u8>(Endian::Big, 1, 0, 0).unwrap_err();
+		assert_eq!(err.to_string(), "Invalid hex input character: 'Þ'");
+		return Ok(());
+	}
+
+	#[test]
+	fn decode_reader() -> Result<(), io::Error> {
+		let data: Vec<u8> = (0..100000_u32).map(|i| (i % 251) as u8).collect();
+		let text: Vec<u8> = data.iter().map(|b| format!("{:02x}\n", b)).collect::<String>().into();
+		let decoder = BaseDecoder::new(&InputFormat::Hex).unwrap();
+		let mut reader = DecodeReader::new(&text[..], decoder);
+		assert_eq!(read_to_vec(&mut reader)?, data);
+		let decoder = BaseDecoder::new(&InputFormat::Hex).unwrap();
+		let err = read_to_vec(&mut DecodeReader::new(&text[..text.len() - 2], decoder)).unwrap_err();
+		assert_eq!(err.to_string(), "Hex input needs an even number of digits");
+		assert!(BaseDecoder::new(&InputFormat::Raw).is_none());
+		return Ok(());
+	}
+
+	#[test]
+	fn open_input_mmap_errors() {
+		let err = open_input("tests/files/read_file", &InputFormat::Hex, MmapMode::Always)
+			.err().unwrap();
+		assert_eq!(err.to_string(), "Only raw input can be memory mapped");
+		let err = open_input("-", &InputFormat::Raw, MmapMode::Always).err().unwrap();
+		assert_eq!(err.to_string(), "Standard input cannot be memory mapped");
+	}
+
+	#[test]
+	fn mapped_file() -> Result<(), io::Error> {
+		let mapped = MappedFile::open("tests/files/read_file")?;
+		assert_eq!(&mapped[..], &read_file("tests/files/read_file")?[..]);
+		let words = WordChunks::<u8, _>::new(&mapped[..], Endian::Big, 2, 1, 0)?.collect_words()?;
+		assert_eq!(words, vec![0xad, 0xef, 0x00, 0x88, 0x11]);
+		let err = MappedFile::open("tests/files/inexistant").err().unwrap();
+		assert_eq!(err.kind(), io::ErrorKind::NotFound);
+		return Ok(());
+	}
+
+	#[test]
+	fn input_words() -> Result<(), io::Error> {
+		let data = read_file("tests/files/read_file")?;
+		let mut mapped = open_input("tests/files/read_file", &InputFormat::Raw, MmapMode::Always)?;
+		assert!(matches!(mapped, Input::Mapped(_)));
+		let words = mapped.words::<u8>(Endian::Big, 1, 2, 4)?;
+		assert!(matches!(words, Cow::Borrowed(_)));
+		assert_eq!(words[..], data[2..6]);
+		let words = mapped.words::<u8>(Endian::Big, 1, 20, 0)?;
+		assert!(words.is_empty());
+		let words = mapped.words::<u8>(Endian::Big, 2, 1, 0)?;
+		assert!(matches!(words, Cow::Owned(_)));
+		assert_eq!(words[..], [0xad, 0xef, 0x00, 0x88, 0x11]);
+		let err = mapped.words::<u16>(Endian::Big, 1, 0, 0).unwrap_err();
+		assert_eq!(err.to_string(), "Vector length needs to be a multiple of T's size (2 bytes)");
+		let mut stream = open_input("tests/files/read_file", &InputFormat::Raw, MmapMode::Never)?;
+		assert!(matches!(stream, Input::Stream(_)));
+		assert_eq!(stream.words::<u8>(Endian::Big, 1, 2, 4)?[..], data[2..6]);
+		return Ok(());
+	}
+
+	#[test]
+	fn mapped_file_empty() -> Result<(), io::Error> {
+		let path = std::env::temp_dir().join("cryptolytics_mapped_file_empty");
+		std::fs::write(&path, b"")?;
+		let mapped = MappedFile::open(path.to_str().unwrap())?;
+		assert!(mapped.is_empty());
+		std::fs::remove_file(&path)?;
+		return Ok(());
+	}
 }
@@ -0,0 +1,90 @@
+//! Reference statistics of natural languages
+
+/// Letter distribution of a language
+pub struct Language {
+	pub name: &'static str,
+	/// Relative frequency in percent of the letters A to Z, letters with
+	/// diacritics are not included
+	pub letter_frequencies: [f64; 26],
+	/// Monogram to quadgram counts in the language model file format, the
+	/// monograms are replaced by letter_frequencies when it is loaded
+	pub model: &'static [u8],
+}
+
+// The n-gram counts were made with the train method from about 1.6 to 1.9
+// million letters of the messages of the gettext catalogs of a Debian 12
+// system together with the sample text languages/<name>.txt. English takes
+// the original messages, the other languages their translations. Umlauts
+// are written as ae, oe and ue, ß as ss and every other letter without its
+// diacritics, as texts of classical ciphers usually are.
+//
+// The catalogs are part of coreutils, apt, dpkg, bash, glib, gtk and other
+// packages and are covered by their licenses, mostly the GPL and LGPL. The
+// model files hold only n-gram counts, no text of the catalogs. The sample
+// texts are written for this crate.
+//
+// languages/regenerate.sh trains the model files again, with the corpus of
+// the catalogs written by languages/corpus.py.
+
+pub const ENGLISH: Language = Language {
+	name: "english",
+	letter_frequencies: [
+		8.167, 1.492, 2.782, 4.253, 12.702, 2.228, 2.015, 6.094, 6.966, 0.153,
+		0.772, 4.025, 2.406, 6.749, 7.507, 1.929, 0.095, 5.987, 6.327, 9.056,
+		2.758, 0.978, 2.360, 0.150, 1.974, 0.074],
+	model: include_bytes!("languages/english.clm"),
+};
+
+pub const GERMAN: Language = Language {
+	name: "german",
+	letter_frequencies: [
+		6.516, 1.886, 2.732, 5.076, 16.396, 1.656, 3.009, 4.577, 6.550, 0.268,
+		1.417, 3.437, 2.534, 9.776, 2.594, 0.670, 0.018, 7.003, 7.270, 6.154,
+		4.166, 0.846, 1.921, 0.034, 0.039, 1.134],
+	model: include_bytes!("languages/german.clm"),
+};
+
+pub const FRENCH: Language = Language {
+	name: "french",
+	letter_frequencies: [
+		7.636, 0.901, 3.260, 3.669, 14.715, 1.066, 0.866, 0.737, 7.529, 0.613,
+		0.074, 5.456, 2.968, 7.095, 5.796, 2.521, 1.362, 6.693, 7.948, 7.244,
+		6.311, 1.838, 0.049, 0.427, 0.128, 0.326],
+	model: include_bytes!("languages/french.clm"),
+};
+
+pub const SPANISH: Language = Language {
+	name: "spanish",
+	letter_frequencies: [
+		11.525, 2.215, 4.019, 5.010, 12.181, 0.692, 1.768, 0.703, 6.247, 0.493,
+		0.011, 4.967, 3.157, 6.712, 8.683, 2.510, 0.877, 6.871, 7.977, 4.632,
+		2.927, 1.138, 0.017, 0.215, 1.008, 0.467],
+	model: include_bytes!("languages/spanish.clm"),
+};
+
+pub const LANGUAGES: [&Language; 4] = [&ENGLISH, &GERMAN, &FRENCH, &SPANISH];
+
+impl Language {
+	/// Probability of every letter, normalized to a sum of 1
+	pub fn letter_probabilities(&self) -> [f64; 26] {
+		let total: f64 = self.letter_frequencies.iter().sum();
+		let mut result = [0.0; 26];
+		for (i, f) in self.letter_frequencies.iter().enumerate() {
+			result[i] = f / total;
+		}
+		return result;
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::languages::LANGUAGES;
+
+	#[test]
+	fn letter_probabilities() {
+		for language in LANGUAGES {
+			let sum: f64 = language.letter_probabilities().iter().sum();
+			assert!((sum - 1.0).abs() < 1e-9);
+		}
+	}
+}
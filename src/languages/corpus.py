@@ -0,0 +1,81 @@
+#!/usr/bin/env python3
+"""Write the training corpus of one language to stdout.
+
+The corpus are the messages of the gettext catalogs in /usr/share/locale:
+the original english messages of every catalog, or the translations of the
+catalogs of the given locale. Format strings, options, paths and numbers are
+dropped. Umlauts are written as ae, oe and ue, sharp s as ss and every other
+letter without its diacritics. The catalogs of iso-codes are left out, they
+are lists of names.
+
+Usage: corpus.py en|de|fr|es
+"""
+import glob
+import os
+import re
+import struct
+import sys
+import unicodedata
+
+GERMAN = {'ä': 'ae', 'ö': 'oe', 'ü': 'ue', 'Ä': 'Ae', 'Ö': 'Oe', 'Ü': 'Ue', 'ß': 'ss'}
+SKIPPED_WORD = re.compile(r"[%$\\/_=<>{}\[\]@#0-9]|^-|\.\w")
+
+
+def read_mo(path):
+    """Pairs of original message and translation, without the header"""
+    data = open(path, 'rb').read()
+    endian = '<' if struct.unpack('<I', data[:4])[0] == 0x950412de else '>'
+    _, _, count, originals, translations = struct.unpack(endian + '5I', data[:20])
+    result = []
+    for i in range(1, count):
+        length, offset = struct.unpack(endian + '2I', data[originals + 8 * i:originals + 8 * i + 8])
+        original = data[offset:offset + length].decode('utf-8', 'ignore')
+        length, offset = struct.unpack(
+            endian + '2I', data[translations + 8 * i:translations + 8 * i + 8])
+        translation = data[offset:offset + length].decode('utf-8', 'ignore')
+        result.append((original, translation))
+    return result
+
+
+def transliterate(text, locale):
+    if locale == 'de':
+        for letter, replacement in GERMAN.items():
+            text = text.replace(letter, replacement)
+    text = unicodedata.normalize('NFKD', text)
+    return ''.join(c for c in text if not unicodedata.combining(c))
+
+
+def clean(text):
+    return ' '.join(w for w in text.split() if not SKIPPED_WORD.search(w))
+
+
+def catalogs(locale):
+    return sorted(
+        f for f in glob.glob('/usr/share/locale/%s/LC_MESSAGES/*.mo' % locale)
+        if not os.path.basename(f).startswith('iso_'))
+
+
+def corpus(locale):
+    lines = []
+    seen = set()
+    if locale == 'en':
+        for f in catalogs('*'):
+            for original, _ in read_mo(f):
+                # Plural forms are separated by NUL
+                for message in original.split('\x00'):
+                    if message not in seen:
+                        seen.add(message)
+                        lines.append(clean(message))
+    else:
+        for f in catalogs(locale):
+            for original, translation in read_mo(f):
+                originals = original.split('\x00')
+                for i, message in enumerate(translation.split('\x00')):
+                    if message and message not in originals[i:i + 1] and message not in seen:
+                        seen.add(message)
+                        lines.append(clean(transliterate(message, locale)))
+    return '\n'.join(lines)
+
+
+if __name__ == '__main__':
+    sys.stdout.write(corpus(sys.argv[1]))
@@ -0,0 +1,14 @@
+#!/bin/sh
+# Train the embedded language models again from the gettext catalogs of the
+# system and the sample texts. Run from the root of the repository.
+set -e
+cargo build --release
+for language in en:english de:german fr:french es:spanish; do
+	locale=${language%%:*}
+	name=${language#*:}
+	corpus=$(mktemp)
+	python3 src/languages/corpus.py "$locale" > "$corpus"
+	cat "src/languages/$name.txt" >> "$corpus"
+	./target/release/cryptolytics train --save "src/languages/$name.clm" --name "$name" "$corpus"
+	rm "$corpus"
+done
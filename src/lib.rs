@@ -0,0 +1,29 @@
+//! Classical cryptoanalysis of ciphertexts
+//!
+//! The input is loaded with [`input::read_file`], decoded with
+//! [`input::decode_input`], grouped into words with [`input::convert_vec`]
+//! and filtered with [`input::filter_input_vec`]. The resulting vector of
+//! words can be passed to every method in [`analytics`] and [`solvers`].
+//! Large inputs can be read with [`input::WordChunks`] instead, which yields
+//! the filtered words in chunks for the `_chunks` methods in [`analytics`].
+//! [`input::open_input`] memory maps large raw files, their single byte
+//! words are passed to the methods without copying them.
+//! Candidate plaintexts are rated with the language models in [`scoring`].
+//!
+//! ```no_run
+//! use cryptolytics::analytics;
+//! use cryptolytics::input;
+//!
+//! let raw = input::read_file("ciphertext.bin").unwrap();
+//! let words = input::convert_vec::<u16>(&raw, &input::Endian::Little).unwrap();
+//! let column = input::filter_input_vec(&words, 4, 1).unwrap();
+//! let ic = analytics::index_of_coincidence(&column).unwrap();
+//! ```
+
+pub mod analytics;
+pub mod input;
+pub mod languages;
+pub mod output;
+pub mod scoring;
+pub mod solvers;
+pub mod types;
@@ -1,3 +1,4 @@
+use std::borrow::Cow;
 use std::cmp::PartialOrd;
 use std::env;
 use std::fmt::Debug;
@@ -6,36 +7,168 @@ use std::hash::Hash;
 use std::ops::AddAssign;
 use std::ops::Shl;
 use std::io;
-mod analytics;
-mod input;
-mod types;
+use std::convert::TryFrom;
+use cryptolytics::analytics;
+use cryptolytics::input;
+use cryptolytics::output;
+use cryptolytics::scoring;
+use cryptolytics::solvers;
+use cryptolytics::types;
 mod arguments;
 
 
-fn main_type<
+// Analyses which consume the input chunk by chunk
+fn main_chunks<
 	T: Copy + AddAssign + Eq + Hash + Debug + Shl + From<u8> +
-	   From<<T as Shl>::Output> + types::NumBytes + Display + PartialOrd
->(action: &arguments::Action) -> Result<(), io::Error> {
-	let input = input::read_file(&action.filename)?;
-	let conv_vec = input::convert_vec::<T>(&input)?;
-	let vec = input::filter_input_vec(
-		&conv_vec, action.keep_every, action.skip_first)?;
-	let mut stdout = io::stdout();
-	let mut out = io::Stdout::lock(&mut stdout);
-	match action.method {
-		arguments::AnalyzeMethod::None => (),
-		arguments::AnalyzeMethod::MinMax => {
-			let res = analytics::min_max(&vec)?;
-			analytics::print_min_max_result(&res, &mut out)?;
-		},
-		arguments::AnalyzeMethod::FrequencyAnalysis => {
-			let res = analytics::frequency_analysis(&vec);
-			analytics::print_frequency_analysis_result(res, &mut out)?;
-		},
-		arguments::AnalyzeMethod::KasiskiExamination(l) => {
-			let res = analytics::kasiski_examination(&vec, l);
-			analytics::print_kasiski_examination_result(&res, &mut out)?;
-			analytics::print_kasiski_examination_total(&res, &mut out)?;
+	   From<<T as Shl>::Output> + types::NumBytes + Display + PartialOrd +
+	   Into<u128> + TryFrom<u128>
+>(action: &arguments::Action, method: &arguments::ChunkMethod) -> Result<(), io::Error> {
+	let mut input = input::open_input(&action.filename, &action.input_format, action.mmap)?;
+	let chunks = input::WordChunks::<T, _>::new(
+		input.reader(), action.endian, action.keep_every, action.skip_first, action.length)?;
+	let stdout = io::stdout();
+	let mut out = io::Stdout::lock(&stdout);
+	let format = &action.output;
+	match method {
+		arguments::ChunkMethod::MinMax => {
+			let res = analytics::min_max_chunks(chunks)?;
+			analytics::print_min_max_result(&res, format, &mut out)?;
+		},
+		arguments::ChunkMethod::FrequencyAnalysis => {
+			let res = analytics::frequency_analysis_chunks(chunks)?;
+			analytics::print_frequency_analysis_result(res, format, &mut out)?;
+		},
+		arguments::ChunkMethod::Entropy(0, _) => {
+			let res = analytics::entropy_chunks(chunks)?;
+			analytics::print_entropy_result(res, format, &mut out)?;
+		},
+		arguments::ChunkMethod::Entropy(w, s) => {
+			let res = analytics::entropy_profile_chunks(chunks, *w, *s)?;
+			analytics::print_entropy_profile(&res, format, &mut out)?;
+		},
+	};
+	return Ok(());
+}
+
+// The language model of --language-model, if given
+fn language_models(action: &arguments::Action) -> Result<Vec<scoring::LanguageModel>, io::Error> {
+	return match &action.language_model {
+		Some(f) => Ok(vec![scoring::load_language_model(f)?]),
+		None => Ok(vec![]),
+	};
+}
+
+// The language model of --language-model, english without it, for the
+// solvers which work on letters
+fn language_model(action: &arguments::Action)
+		-> Result<Cow<'static, scoring::LanguageModel>, io::Error> {
+	return match &action.language_model {
+		Some(f) => {
+			let model = scoring::load_language_model(f)?;
+			model.require_letters()?;
+			Ok(Cow::Owned(model))
+		},
+		// The first of the built-in languages
+		None => Ok(Cow::Borrowed(&scoring::builtin_models()[0])),
+	};
+}
+
+// Analyses which need the whole input
+fn main_vec<
+	T: Copy + AddAssign + Eq + Hash + Debug + Shl + From<u8> +
+	   From<<T as Shl>::Output> + types::NumBytes + Display + PartialOrd +
+	   Into<u128> + TryFrom<u128>
+>(action: &arguments::Action, method: &arguments::WholeMethod) -> Result<(), io::Error> {
+	let mut input = input::open_input(&action.filename, &action.input_format, action.mmap)?;
+	let vec: &[T] = &input.words::<T>(
+		action.endian, action.keep_every, action.skip_first, action.length)?;
+	let stdout = io::stdout();
+	let mut out = io::Stdout::lock(&stdout);
+	let format = &action.output;
+	match *method {
+		arguments::WholeMethod::NgramFrequencyAnalysis(n, overlapping) => {
+			let res = analytics::ngram_frequency_analysis(vec, n, overlapping)?;
+			analytics::print_ngram_frequency_analysis_result(&res, format, &mut out)?;
+		},
+		arguments::WholeMethod::KasiskiExamination(l) => {
+			let res = analytics::kasiski_examination(vec, l);
+			analytics::print_kasiski_examination_result(&res, format, &mut out)?;
+			// A CSV output can only hold one table
+			if *format != output::OutputFormat::Csv {
+				analytics::print_kasiski_examination_total(&res, format, &mut out)?;
+				let periods = analytics::kasiski_period_candidates(&res);
+				analytics::print_kasiski_period_candidates(&periods, format, &mut out)?;
+			}
+		},
+		arguments::WholeMethod::IndexOfCoincidence => {
+			let res = analytics::index_of_coincidence(vec)?;
+			analytics::print_index_of_coincidence_result(res, format, &mut out)?;
+		},
+		arguments::WholeMethod::PeriodSweep(m) => {
+			let res = analytics::period_sweep(vec, m)?;
+			analytics::print_period_sweep_result(&res, format, &mut out)?;
+			if *format != output::OutputFormat::Csv {
+				let ic = analytics::index_of_coincidence(vec)?;
+				let estimate = analytics::friedman_test(ic, vec.len());
+				analytics::print_friedman_test_result(estimate, format, &mut out)?;
+			}
+		},
+		arguments::WholeMethod::ChiSquared => {
+			let res = analytics::chi_squared(vec, &language_models(action)?)?;
+			analytics::print_chi_squared_result(&res, format, &mut out)?;
+		},
+		arguments::WholeMethod::Caesar(alphabet, top) => {
+			let res = solvers::caesar(vec, &alphabet, top);
+			solvers::print_caesar_result(&res, format, &mut out)?;
+		},
+		arguments::WholeMethod::Vigenere(alphabet, max_period, key_length) => {
+			let res = solvers::vigenere(vec, &alphabet, max_period, key_length)?;
+			solvers::print_vigenere_result(&res, &alphabet, format, &mut out)?;
+		},
+		arguments::WholeMethod::XorRepeating(max_period, key_length) => {
+			let res = solvers::xor_repeating(vec, max_period, key_length)?;
+			solvers::print_xor_repeating_result(&res, format, &mut out)?;
+		},
+		arguments::WholeMethod::XorSingle(top) => {
+			let res = solvers::xor_single(vec, &action.endian, top)?;
+			solvers::print_xor_single_result(&res, format, &mut out)?;
+		},
+		arguments::WholeMethod::Substitution(restarts) => {
+			let model = language_model(action)?;
+			let res = solvers::substitution(vec, &*model, restarts)?;
+			solvers::print_substitution_result(&res, format, &mut out)?;
+		},
+		arguments::WholeMethod::Transposition(max_period, key_length, top) => {
+			let model = language_model(action)?;
+			if *format != output::OutputFormat::Csv {
+				let detection = solvers::transposition_detection(vec, &model)?;
+				solvers::print_transposition_detection(&detection, format, &mut out)?;
+			}
+			let res = solvers::columnar_transposition(
+				vec, model.ngram(2)?, max_period, key_length, top)?;
+			solvers::print_transposition_result(&res, format, &mut out)?;
+		},
+		arguments::WholeMethod::RailFence(max_rails, top) => {
+			let res = solvers::rail_fence(vec, &*language_model(action)?, max_rails, top)?;
+			solvers::print_rail_fence_result(&res, format, &mut out)?;
+		},
+		arguments::WholeMethod::Score => {
+			let loaded = language_models(action)?;
+			// The built-in models are made of letters, a model of another
+			// alphabet is scored alone
+			let builtin = match loaded.first() {
+				Some(m) if m.alphabet != scoring::Alphabet::Letters => &[],
+				_ => scoring::builtin_models(),
+			};
+			let models: Vec<&scoring::LanguageModel> =
+				builtin.iter().chain(loaded.iter()).collect();
+			let res = scoring::plaintext_score(vec, &models)?;
+			scoring::print_plaintext_score_result(&res, format, &mut out)?;
+		},
+		arguments::WholeMethod::Train(ref model_file, ref name, alphabet) => {
+			let model = scoring::train_language_model(name, alphabet, vec)?;
+			scoring::save_language_model(&model, model_file)?;
+			scoring::print_language_model_summary(&model, format, &mut out)?;
 		},
 	};
 	return Ok(());
@@ -54,12 +187,18 @@ fn main() {
 		arguments::help(&args[0]);
 		return;
 	}
-	let result = match action.size {
-		arguments::Sizes::U8 => main_type::<u8>(&action),
-		arguments::Sizes::U16 => main_type::<u16>(&action),
-		arguments::Sizes::U32 => main_type::<u32>(&action),
-		arguments::Sizes::U64 => main_type::<u64>(&action),
-		arguments::Sizes::U128 => main_type::<u128>(&action),
+	let result = match (&action.method, &action.size) {
+		(arguments::AnalyzeMethod::None, _) => Ok(()),
+		(arguments::AnalyzeMethod::Chunks(m), arguments::Sizes::U8) => main_chunks::<u8>(&action, m),
+		(arguments::AnalyzeMethod::Chunks(m), arguments::Sizes::U16) => main_chunks::<u16>(&action, m),
+		(arguments::AnalyzeMethod::Chunks(m), arguments::Sizes::U32) => main_chunks::<u32>(&action, m),
+		(arguments::AnalyzeMethod::Chunks(m), arguments::Sizes::U64) => main_chunks::<u64>(&action, m),
+		(arguments::AnalyzeMethod::Chunks(m), arguments::Sizes::U128) => main_chunks::<u128>(&action, m),
+		(arguments::AnalyzeMethod::Whole(m), arguments::Sizes::U8) => main_vec::<u8>(&action, m),
+		(arguments::AnalyzeMethod::Whole(m), arguments::Sizes::U16) => main_vec::<u16>(&action, m),
+		(arguments::AnalyzeMethod::Whole(m), arguments::Sizes::U32) => main_vec::<u32>(&action, m),
+		(arguments::AnalyzeMethod::Whole(m), arguments::Sizes::U64) => main_vec::<u64>(&action, m),
+		(arguments::AnalyzeMethod::Whole(m), arguments::Sizes::U128) => main_vec::<u128>(&action, m),
 	};
 	std::process::exit(match result {
 		Ok(()) => 0,
@@ -0,0 +1,183 @@
+use std::fmt::Display;
+use std::io::Write;
+use std::io;
+
+/// Format the print functions of the analytics write
+#[derive(PartialEq, Debug)]
+pub enum OutputFormat {
+	Text,
+	// One JSON object per line
+	Json,
+	// Header line followed by one line per row
+	Csv,
+}
+
+/// Write a float as JSON number, JSON has no representation for NaN and
+/// infinity
+pub fn write_json_float<W: Write>(value: f64, out: &mut W) -> Result<(), io::Error> {
+	if value.is_finite() {
+		write!(out, "{}", value)?;
+	} else {
+		write!(out, "null")?;
+	}
+	return Ok(());
+}
+
+/// Write a list separated by commas for the text output
+pub fn write_text_list<
+	T: Display, W: Write
+>(vec: &[T], out: &mut W) -> Result<(), io::Error> {
+	let mut first = true;
+	for k in vec {
+		if first {
+			write!(out, "{}", k)?;
+			first = false;
+		} else {
+			write!(out, ", {}", k)?;
+		}
+	}
+	return Ok(());
+}
+
+/// Write a list as JSON array of numbers
+pub fn write_json_array<
+	T: Display, W: Write
+>(vec: &[T], out: &mut W) -> Result<(), io::Error> {
+	write!(out, "[")?;
+	let mut first = true;
+	for k in vec {
+		if first {
+			write!(out, "{}", k)?;
+			first = false;
+		} else {
+			write!(out, ",{}", k)?;
+		}
+	}
+	write!(out, "]")?;
+	return Ok(());
+}
+
+/// Write a list as a single CSV field, separated by spaces
+pub fn write_csv_list<
+	T: Display, W: Write
+>(vec: &[T], out: &mut W) -> Result<(), io::Error> {
+	let mut first = true;
+	for k in vec {
+		if first {
+			write!(out, "{}", k)?;
+			first = false;
+		} else {
+			write!(out, " {}", k)?;
+		}
+	}
+	return Ok(());
+}
+
+/// Printable representation of words as characters, printable ASCII is kept,
+/// a backslash and every other word is escaped as \xNN
+pub fn words_to_text<T: Copy + Into<u128>>(vec: &Vec<T>) -> String {
+	let mut result = String::with_capacity(vec.len());
+	for w in vec {
+		let v: u128 = (*w).into();
+		if v == u128::from(b'\\') {
+			result.push_str("\\\\");
+		} else if (0x20..0x7f).contains(&v) {
+			result.push(v as u8 as char);
+		} else {
+			result.push_str(&format!("\\x{:02x}", v));
+		}
+	}
+	return result;
+}
+
+/// Write a string as quoted JSON string
+pub fn write_json_string<W: Write>(value: &str, out: &mut W) -> Result<(), io::Error> {
+	write!(out, "\"")?;
+	for c in value.chars() {
+		match c {
+			'"' => write!(out, "\\\"")?,
+			'\\' => write!(out, "\\\\")?,
+			c if (c as u32) < 0x20 => write!(out, "\\u{:04x}", c as u32)?,
+			c => write!(out, "{}", c)?,
+		};
+	}
+	write!(out, "\"")?;
+	return Ok(());
+}
+
+/// Write a string as quoted CSV field
+pub fn write_csv_string<W: Write>(value: &str, out: &mut W) -> Result<(), io::Error> {
+	write!(out, "\"{}\"", value.replace('"', "\"\""))?;
+	return Ok(());
+}
+
+#[cfg(test)]
+mod tests {
+	use std::io::Write;
+	use crate::output::write_json_float;
+	use crate::output::write_text_list;
+	use crate::output::write_json_array;
+	use crate::output::write_csv_list;
+	use crate::output::words_to_text;
+	use crate::output::write_json_string;
+	use crate::output::write_csv_string;
+
+	#[test]
+	fn write_json_float_test() {
+		let mut out = Vec::new();
+		write_json_float(0.25, &mut out).unwrap();
+		write_json_float(f64::INFINITY, &mut out).unwrap();
+		write_json_float(f64::NAN, &mut out).unwrap();
+		write_json_float(-3.0, &mut out).unwrap();
+		assert_eq!(std::str::from_utf8(&out), Ok("0.25nullnull-3"));
+	}
+
+	#[test]
+	fn write_text_list_test() {
+		let mut out = Vec::new();
+		write_text_list::<u8, _>(&[], &mut out).unwrap();
+		write!(out, "|").unwrap();
+		write_text_list(&[24_u8], &mut out).unwrap();
+		write!(out, "|").unwrap();
+		write_text_list(&[24_u8, 5, 32, 7], &mut out).unwrap();
+		assert_eq!(std::str::from_utf8(&out), Ok("|24|24, 5, 32, 7"));
+	}
+
+	#[test]
+	fn write_json_array_test() {
+		let mut out = Vec::new();
+		write_json_array::<u8, _>(&[], &mut out).unwrap();
+		write_json_array(&[24_u128, 5, 32], &mut out).unwrap();
+		assert_eq!(std::str::from_utf8(&out), Ok("[][24,5,32]"));
+	}
+
+	#[test]
+	fn write_csv_list_test() {
+		let mut out = Vec::new();
+		write_csv_list::<u8, _>(&[], &mut out).unwrap();
+		write!(out, ",").unwrap();
+		write_csv_list(&[24_u16, 5, 32], &mut out).unwrap();
+		assert_eq!(std::str::from_utf8(&out), Ok(",24 5 32"));
+	}
+
+	#[test]
+	fn words_to_text_test() {
+		assert_eq!(words_to_text(&b"Hi \\ \"x\"~".to_vec()), "Hi \\\\ \"x\"~");
+		assert_eq!(words_to_text(&vec![0x0a_u8, 0x7f, 0xff]), "\\x0a\\x7f\\xff");
+		assert_eq!(words_to_text(&vec![0x41_u32, 0x1234]), "A\\x1234");
+	}
+
+	#[test]
+	fn write_json_string_test() {
+		let mut out = Vec::new();
+		write_json_string("a\"b\\c\n", &mut out).unwrap();
+		assert_eq!(std::str::from_utf8(&out), Ok("\"a\\\"b\\\\c\\u000a\""));
+	}
+
+	#[test]
+	fn write_csv_string_test() {
+		let mut out = Vec::new();
+		write_csv_string("a,\"b\"", &mut out).unwrap();
+		assert_eq!(std::str::from_utf8(&out), Ok("\"a,\"\"b\"\"\""));
+	}
+}
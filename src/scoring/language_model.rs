@@ -0,0 +1,172 @@
+use std::io;
+use std::sync::OnceLock;
+use super::model_file::read_language_model;
+use super::ngrams::LETTERS;
+use super::ngrams::Alphabet;
+use super::ngrams::NgramTable;
+use super::ngrams::letter_indices;
+use crate::languages::Language;
+use crate::languages::LANGUAGES;
+
+/// Rates how much letters look like plaintext, higher is more likely
+pub trait Scorer {
+	/// Score of letter indices 0 to 25, or of the symbol indices of the
+	/// alphabet of a language model
+	fn score(&self, letters: &[u8]) -> f64;
+
+	/// Score of the letters of a vector of words, other words are ignored
+	fn score_words<T: Copy + Into<u128>>(&self, vec: &[T]) -> f64 where Self: Sized {
+		return self.score(&letter_indices(vec));
+	}
+}
+
+impl Scorer for NgramTable {
+	fn score(&self, letters: &[u8]) -> f64 {
+		return NgramTable::score(self, letters);
+	}
+}
+
+/// N-gram tables of a language
+#[derive(PartialEq, Debug, Clone)]
+pub struct LanguageModel {
+	pub name: String,
+	/// Symbols of the n-grams
+	pub alphabet: Alphabet,
+	/// Table of n-grams of length n at index n - 1, up to the max_ngram of
+	/// the alphabet
+	pub ngrams: Vec<NgramTable>,
+}
+
+impl LanguageModel {
+	/// Count the n-grams of the words which are in the alphabet, every other
+	/// word is ignored
+	pub fn from_words<
+		T: Copy + Into<u128>
+	>(name: &str, alphabet: Alphabet, vec: &[T]) -> LanguageModel {
+		let indices = alphabet.indices(vec);
+		let ngrams = (1..=alphabet.max_ngram())
+			.map(|n| NgramTable::from_indices(alphabet, &indices, n).expect("N-gram size in range"))
+			.collect();
+		return LanguageModel { name: name.to_string(), alphabet: alphabet, ngrams: ngrams };
+	}
+
+	/// Count the n-grams of the letters of a text, case and every other
+	/// character are ignored
+	pub fn from_text(name: &str, text: &[u8]) -> LanguageModel {
+		return LanguageModel::from_words(name, Alphabet::Letters, text);
+	}
+
+	/// Model of the embedded tables of a built-in language. The monograms
+	/// are the letter frequencies of the language, so the scores agree with
+	/// the chi-squared test.
+	pub fn from_language(language: &Language) -> LanguageModel {
+		let mut model = read_language_model(language.model).expect("Valid embedded language model");
+		// Percentages with 3 decimals
+		let counts = language.letter_frequencies.iter().map(|f| (f * 1000.0).round() as u64).collect();
+		model.ngrams[0] = NgramTable::from_counts(Alphabet::Letters, 1, counts)
+			.expect("Monogram table size");
+		return model;
+	}
+
+	/// Probability of every letter of a model of the letters, normalized to
+	/// a sum of 1. Letters which do not occur in the corpus get the
+	/// probability of an unseen n-gram.
+	pub fn letter_probabilities(&self) -> Result<[f64; LETTERS], io::Error> {
+		self.require_letters()?;
+		let mut result = [0.0; LETTERS];
+		for (i, p) in result.iter_mut().enumerate() {
+			*p = 10_f64.powf(self.ngrams[0].log_probability(&[i as u8]));
+		}
+		let total: f64 = result.iter().sum();
+		for p in result.iter_mut() {
+			*p /= total;
+		}
+		return Ok(result);
+	}
+
+	/// Fails unless this is a model of the letters
+	pub fn require_letters(&self) -> Result<(), io::Error> {
+		if self.alphabet != Alphabet::Letters {
+			return Err(io::Error::other(format!(
+				"Language model {} is a model of the {}, not of the letters",
+				self.name, self.alphabet.symbols())));
+		}
+		return Ok(());
+	}
+
+	/// Table of the n-grams of length n, between 1 and the max_ngram of the
+	/// alphabet
+	pub fn ngram(&self, n: usize) -> Result<&NgramTable, io::Error> {
+		return match n {
+			0 => None,
+			n => self.ngrams.get(n - 1),
+		}.ok_or_else(|| io::Error::other(format!("No {}-gram table in the language model", n)));
+	}
+}
+
+/// Scores with the longest n-grams, the quadgrams of the letters are the
+/// most reliable table for texts of more than a few words
+impl Scorer for LanguageModel {
+	fn score(&self, letters: &[u8]) -> f64 {
+		return self.ngrams[self.ngrams.len() - 1].score(letters);
+	}
+
+	fn score_words<T: Copy + Into<u128>>(&self, vec: &[T]) -> f64 {
+		return self.score(&self.alphabet.indices(vec));
+	}
+}
+
+/// Models of every built-in language in the order of LANGUAGES, built once
+/// on the first call
+pub fn builtin_models() -> &'static [LanguageModel] {
+	static MODELS: OnceLock<Vec<LanguageModel>> = OnceLock::new();
+	return MODELS.get_or_init(|| LANGUAGES.iter().map(|l| LanguageModel::from_language(l)).collect());
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::languages::ENGLISH;
+	use crate::languages::GERMAN;
+	use crate::scoring::language_model::LanguageModel;
+	use crate::scoring::language_model::builtin_models;
+	use crate::scoring::language_model::Scorer;
+
+	#[test]
+	fn language_model_tables() {
+		let model = LanguageModel::from_text("test", b"abcab");
+		assert_eq!(model.name, "test");
+		for n in 1..=4 {
+			assert_eq!(model.ngram(n).unwrap().n(), n);
+		}
+		assert_eq!(model.ngram(2).unwrap().counts()[1], 2);
+		assert_eq!(model.ngram(0).unwrap_err().to_string(), "No 0-gram table in the language model");
+		assert_eq!(model.ngram(5).unwrap_err().to_string(), "No 5-gram table in the language model");
+		let p = model.letter_probabilities().unwrap();
+		assert!((p.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+		// 2 of 5 letters, the 23 unseen letters count 0.01 each
+		assert!((p[0] - 2.0 / 5.23).abs() < 1e-9);
+		assert!((p[3] - 0.01 / 5.23).abs() < 1e-9);
+	}
+
+	#[test]
+	fn language_model_from_language() {
+		let model = LanguageModel::from_language(&GERMAN);
+		assert_eq!(model.name, "german");
+		let p = model.letter_probabilities().unwrap();
+		for (a, b) in p.iter().zip(GERMAN.letter_probabilities().iter()) {
+			assert!((a - b).abs() < 1e-9);
+		}
+		assert!(model.ngram(4).unwrap().counts().iter().sum::<u64>() > 1000000);
+		assert!(std::ptr::eq(builtin_models(), builtin_models()));
+		assert_eq!(builtin_models()[0].name, "english");
+	}
+
+	#[test]
+	fn language_model_score() {
+		let model = LanguageModel::from_language(&ENGLISH);
+		let plain = model.score_words(&b"The weather is fine today."[..]);
+		let shuffled = model.score_words(&b"Teh wteahre si feni otady."[..]);
+		assert!(plain > shuffled);
+		assert_eq!(model.score_words(&b"The"[..]), 0.0);
+	}
+}
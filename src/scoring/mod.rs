@@ -0,0 +1,28 @@
+//! N-gram statistics of natural languages to rate how much a text looks like
+//! plaintext, used by the solvers to rank candidate decryptions
+
+mod ngrams;
+pub use ngrams::LETTERS;
+pub use ngrams::MAX_NGRAM;
+pub use ngrams::letter_index;
+pub use ngrams::letter_indices;
+pub use ngrams::Alphabet;
+pub use ngrams::NgramTable;
+
+mod language_model;
+pub use language_model::Scorer;
+pub use language_model::LanguageModel;
+pub use language_model::builtin_models;
+
+mod plaintext_score;
+pub use plaintext_score::PlaintextScore;
+pub use plaintext_score::plaintext_score;
+pub use plaintext_score::print_plaintext_score_result;
+
+mod model_file;
+pub use model_file::train_language_model;
+pub use model_file::write_language_model;
+pub use model_file::read_language_model;
+pub use model_file::save_language_model;
+pub use model_file::load_language_model;
+pub use model_file::print_language_model_summary;
@@ -0,0 +1,274 @@
+use std::convert::TryFrom;
+use std::fs::File;
+use std::io::Write;
+use std::io;
+use super::language_model::LanguageModel;
+use super::ngrams::Alphabet;
+use super::ngrams::NgramTable;
+use crate::input::read_file;
+use crate::output::OutputFormat;
+use crate::output::write_json_string;
+
+// A language model file starts with the magic and the version, followed by
+// the name, the index of the alphabet in ALPHABETS and one table per n-gram
+// length up to the max_ngram of the alphabet. Every number is an unsigned
+// LEB128 varint, a string is its length followed by the UTF-8 bytes and a
+// table is the number of n-grams which occur followed by the difference of
+// the index to the previous one and the count of every such n-gram. Files of
+// version 1 have no alphabet and are models of the letters.
+const MAGIC: &[u8; 4] = b"CLLM";
+const VERSION: u8 = 2;
+const ALPHABETS: [Alphabet; 3] =
+	[Alphabet::Letters, Alphabet::Printable, Alphabet::Bytes];
+
+fn write_varint<W: Write>(mut value: u64, out: &mut W) -> Result<(), io::Error> {
+	while value >= 0x80 {
+		out.write_all(&[(value as u8 & 0x7f) | 0x80])?;
+		value >>= 7;
+	}
+	out.write_all(&[value as u8])?;
+	return Ok(());
+}
+
+fn invalid_file() -> io::Error {
+	return io::Error::other("Invalid language model file");
+}
+
+fn read_varint(data: &[u8], pos: &mut usize) -> Result<u64, io::Error> {
+	let mut result: u64 = 0;
+	let mut shift = 0;
+	loop {
+		let byte = *data.get(*pos).ok_or_else(invalid_file)?;
+		*pos += 1;
+		if shift > 63 || (shift == 63 && byte > 1) {
+			return Err(invalid_file());
+		}
+		result |= u64::from(byte & 0x7f) << shift;
+		if byte & 0x80 == 0 {
+			return Ok(result);
+		}
+		shift += 7;
+	}
+}
+
+fn read_usize(data: &[u8], pos: &mut usize) -> Result<usize, io::Error> {
+	return usize::try_from(read_varint(data, pos)?).map_err(|_| invalid_file());
+}
+
+/// Build the tables of a language model from a plaintext corpus, every word
+/// which is not in the alphabet is ignored
+pub fn train_language_model<
+	T: Copy + Into<u128>
+>(name: &str, alphabet: Alphabet, vec: &[T]) -> Result<LanguageModel, io::Error> {
+	let symbols = vec.iter().filter(|w| alphabet.index((**w).into()).is_some()).count();
+	if symbols < alphabet.max_ngram() {
+		return Err(io::Error::other(format!(
+			"The corpus needs at least {} {}", alphabet.max_ngram(), alphabet.symbols())));
+	}
+	return Ok(LanguageModel::from_words(name, alphabet, vec));
+}
+
+/// Write a language model in the compact file format
+pub fn write_language_model<W: Write>(model: &LanguageModel, out: &mut W) -> Result<(), io::Error> {
+	out.write_all(MAGIC)?;
+	out.write_all(&[VERSION])?;
+	write_varint(model.name.len() as u64, out)?;
+	out.write_all(model.name.as_bytes())?;
+	let alphabet = ALPHABETS.iter().position(|a| *a == model.alphabet).expect("Known alphabet");
+	write_varint(alphabet as u64, out)?;
+	for n in 1..=model.alphabet.max_ngram() {
+		let counts = model.ngram(n)?.counts();
+		write_varint(counts.iter().filter(|c| **c > 0).count() as u64, out)?;
+		let mut previous = 0;
+		for (i, c) in counts.iter().enumerate().filter(|(_, c)| **c > 0) {
+			write_varint((i - previous) as u64, out)?;
+			write_varint(*c, out)?;
+			previous = i;
+		}
+	}
+	return Ok(());
+}
+
+/// Read a language model in the compact file format
+pub fn read_language_model(data: &[u8]) -> Result<LanguageModel, io::Error> {
+	if data.len() < MAGIC.len() + 1 || data[..MAGIC.len()] != MAGIC[..] {
+		return Err(invalid_file());
+	}
+	let version = data[MAGIC.len()];
+	if version == 0 || version > VERSION {
+		return Err(io::Error::other(
+			format!("Unsupported language model version {}", version)));
+	}
+	let mut pos = MAGIC.len() + 1;
+	let name_length = read_usize(data, &mut pos)?;
+	let name_end = pos.checked_add(name_length).ok_or_else(invalid_file)?;
+	let name = data.get(pos..name_end).ok_or_else(invalid_file)?;
+	let name = String::from_utf8(name.to_vec()).map_err(|_| invalid_file())?;
+	pos = name_end;
+	let alphabet = match version {
+		1 => Alphabet::Letters,
+		_ => *ALPHABETS.get(read_usize(data, &mut pos)?).ok_or_else(invalid_file)?,
+	};
+	let mut ngrams: Vec<NgramTable> = Vec::with_capacity(alphabet.max_ngram());
+	for n in 1..=alphabet.max_ngram() {
+		let mut counts: Vec<u64> = vec![0; alphabet.size().pow(n as u32)];
+		let entries = read_usize(data, &mut pos)?;
+		let mut index: usize = 0;
+		for k in 0..entries {
+			let delta = read_usize(data, &mut pos)?;
+			if k > 0 && delta == 0 {
+				return Err(invalid_file());
+			}
+			index = index.checked_add(delta).ok_or_else(invalid_file)?;
+			let count = counts.get_mut(index).ok_or_else(invalid_file)?;
+			*count = read_varint(data, &mut pos)?;
+		}
+		ngrams.push(NgramTable::from_counts(alphabet, n, counts)?);
+	}
+	if pos != data.len() {
+		return Err(invalid_file());
+	}
+	return Ok(LanguageModel { name: name, alphabet: alphabet, ngrams: ngrams });
+}
+
+/// Write a language model to a file
+pub fn save_language_model(model: &LanguageModel, filename: &str) -> Result<(), io::Error> {
+	let mut data: Vec<u8> = Vec::new();
+	write_language_model(model, &mut data)?;
+	let mut file = File::create(filename)?;
+	file.write_all(&data)?;
+	return Ok(());
+}
+
+/// Read a language model file, - for stdin
+pub fn load_language_model(filename: &str) -> Result<LanguageModel, io::Error> {
+	return read_language_model(&read_file(filename)?);
+}
+
+/// Write the number of distinct n-grams and the total count of every table
+/// of a language model in the given format
+pub fn print_language_model_summary<
+	W: Write
+>(model: &LanguageModel, format: &OutputFormat, out: &mut W) -> Result<(), io::Error> {
+	let mut tables: Vec<(usize, usize, u64)> = Vec::with_capacity(model.ngrams.len());
+	for table in model.ngrams.iter() {
+		let distinct = table.counts().iter().filter(|c| **c > 0).count();
+		tables.push((table.n(), distinct, table.counts().iter().sum()));
+	}
+	match format {
+		OutputFormat::Text => {
+			writeln!(out, "Language model: {}", model.name)?;
+			writeln!(out, "Alphabet: {}", model.alphabet.name())?;
+			for (n, distinct, total) in tables {
+				writeln!(out, "{}-grams: {} distinct, {} total", n, distinct, total)?;
+			}
+		},
+		OutputFormat::Json => {
+			write!(out, "{{\"language\":")?;
+			write_json_string(&model.name, out)?;
+			write!(out, ",\"alphabet\":\"{}\",\"ngrams\":[", model.alphabet.name())?;
+			for (k, (n, distinct, total)) in tables.iter().enumerate() {
+				if k > 0 {
+					write!(out, ",")?;
+				}
+				write!(out, "{{\"n\":{},\"distinct\":{},\"total\":{}}}", n, distinct, total)?;
+			}
+			writeln!(out, "]}}")?;
+		},
+		OutputFormat::Csv => {
+			writeln!(out, "n,distinct,total")?;
+			for (n, distinct, total) in tables {
+				writeln!(out, "{},{},{}", n, distinct, total)?;
+			}
+		},
+	};
+	return Ok(());
+}
+
+#[cfg(test)]
+mod tests {
+	use std::io::Write;
+	use crate::output::OutputFormat;
+	use crate::scoring::language_model::LanguageModel;
+	use crate::scoring::ngrams::Alphabet;
+	use crate::scoring::model_file::train_language_model;
+	use crate::scoring::model_file::write_language_model;
+	use crate::scoring::model_file::read_language_model;
+	use crate::scoring::model_file::save_language_model;
+	use crate::scoring::model_file::load_language_model;
+	use crate::scoring::model_file::print_language_model_summary;
+
+	#[test]
+	fn language_model_file_roundtrip() {
+		let corpus = b"GET /index.html 200 GET /about 404";
+		let model = train_language_model("logs", Alphabet::Letters, corpus).unwrap();
+		let mut data = Vec::new();
+		write_language_model(&model, &mut data).unwrap();
+		assert_eq!(data[..11], *b"CLLM\x02\x04logs\x00");
+		assert_eq!(read_language_model(&data).unwrap(), model);
+		for alphabet in [Alphabet::Printable, Alphabet::Bytes] {
+			let model = train_language_model("logs", alphabet, corpus).unwrap();
+			assert_eq!(model.ngrams.len(), alphabet.max_ngram());
+			let mut data = Vec::new();
+			write_language_model(&model, &mut data).unwrap();
+			assert_eq!(read_language_model(&data).unwrap(), model);
+		}
+		let filename = std::env::temp_dir().join("cryptolytics_language_model_test");
+		let filename = filename.to_str().unwrap();
+		save_language_model(&model, filename).unwrap();
+		assert_eq!(load_language_model(filename).unwrap(), model);
+		std::fs::remove_file(filename).unwrap();
+	}
+
+	#[test]
+	fn language_model_file_errors() {
+		let err = train_language_model("x", Alphabet::Letters, b"ab 12").unwrap_err();
+		assert_eq!(err.to_string(), "The corpus needs at least 4 letters");
+		let err = train_language_model("x", Alphabet::Printable, b"a\n1").unwrap_err();
+		assert_eq!(err.to_string(), "The corpus needs at least 3 printable characters");
+		let err = train_language_model::<u16>("x", Alphabet::Bytes, &[0x41, 0x100]).unwrap_err();
+		assert_eq!(err.to_string(), "The corpus needs at least 2 bytes");
+		let mut data = Vec::new();
+		write_language_model(&LanguageModel::from_text("x", b"abcd"), &mut data).unwrap();
+		let invalid = "Invalid language model file";
+		assert_eq!(read_language_model(b"CLL").unwrap_err().to_string(), invalid);
+		assert_eq!(read_language_model(b"XLLM\x01").unwrap_err().to_string(), invalid);
+		assert_eq!(
+			read_language_model(b"CLLM\x03").unwrap_err().to_string(),
+			"Unsupported language model version 3");
+		// Unknown alphabet
+		assert_eq!(read_language_model(b"CLLM\x02\x00\x03").unwrap_err().to_string(), invalid);
+		assert_eq!(read_language_model(&data[..data.len() - 1]).unwrap_err().to_string(), invalid);
+		data.push(0);
+		assert_eq!(read_language_model(&data).unwrap_err().to_string(), invalid);
+		// Index of a monogram beyond Z in a file of version 1 without an alphabet
+		assert_eq!(
+			read_language_model(b"CLLM\x01\x00\x01\x1a\x01").unwrap_err().to_string(), invalid);
+		assert_eq!(
+			read_language_model(b"CLLM\x01\x00\x01\xff\xff\xff\xff\xff\xff\xff\xff\xff\x7f\x01")
+				.unwrap_err().to_string(), invalid);
+	}
+
+	#[test]
+	fn print_language_model_summary_test() {
+		let model = LanguageModel::from_text("x", b"abab");
+		let mut out = Vec::new();
+		print_language_model_summary(&model, &OutputFormat::Text, &mut out).unwrap();
+		print_language_model_summary(&model, &OutputFormat::Json, &mut out).unwrap();
+		print_language_model_summary(&model, &OutputFormat::Csv, &mut out).unwrap();
+		let mut expected = Vec::new();
+		writeln!(
+			expected,
+			"Language model: x\nAlphabet: letters\n1-grams: 2 distinct, 4 total\n\
+			2-grams: 2 distinct, 3 total\n\
+			3-grams: 2 distinct, 2 total\n4-grams: 1 distinct, 1 total").unwrap();
+		writeln!(
+			expected,
+			"{{\"language\":\"x\",\"alphabet\":\"letters\",\
+			\"ngrams\":[{{\"n\":1,\"distinct\":2,\"total\":4}},\
+			{{\"n\":2,\"distinct\":2,\"total\":3}},{{\"n\":3,\"distinct\":2,\"total\":2}},\
+			{{\"n\":4,\"distinct\":1,\"total\":1}}]}}").unwrap();
+		writeln!(expected, "n,distinct,total\n1,2,4\n2,2,3\n3,2,2\n4,1,1").unwrap();
+		assert_eq!(String::from_utf8(out).unwrap(), String::from_utf8(expected).unwrap());
+	}
+}
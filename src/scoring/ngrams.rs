@@ -0,0 +1,239 @@
+use std::io;
+
+/// Number of letters A to Z
+pub const LETTERS: usize = 26;
+/// Longest n-gram with a table, quadgrams
+pub const MAX_NGRAM: usize = 4;
+// Count of n-grams which do not occur in the corpus
+const NGRAM_FLOOR: f64 = 0.01;
+// Most n-grams of one table, large alphabets get no tables of the longer
+// n-grams
+const MAX_TABLE_SIZE: usize = 1 << 20;
+
+/// Index of the letters A to Z and a to z, None for every other word
+pub fn letter_index(value: u128) -> Option<u8> {
+	if (u128::from(b'A')..=u128::from(b'Z')).contains(&value) {
+		return Some((value - u128::from(b'A')) as u8);
+	}
+	if (u128::from(b'a')..=u128::from(b'z')).contains(&value) {
+		return Some((value - u128::from(b'a')) as u8);
+	}
+	return None;
+}
+
+/// Letter indices of the words which are letters, all other words are
+/// dropped
+pub fn letter_indices<T: Copy + Into<u128>>(vec: &[T]) -> Vec<u8> {
+	return vec.iter().filter_map(|w| letter_index((*w).into())).collect();
+}
+
+/// Words the n-grams of a language model are made of and which the
+/// substitution ciphers shift
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Alphabet {
+	/// The letters A to Z, n-grams ignore the case and shifts keep it
+	Letters,
+	/// Printable ASCII, 0x20 to 0x7e
+	Printable,
+	/// Every byte value
+	Bytes,
+}
+
+impl Alphabet {
+	/// Number of symbols, which is also the number of possible shifts
+	pub fn size(&self) -> usize {
+		return match self {
+			Alphabet::Letters => LETTERS,
+			Alphabet::Printable => 95,
+			Alphabet::Bytes => 256,
+		};
+	}
+
+	pub fn name(&self) -> &'static str {
+		return match self {
+			Alphabet::Letters => "letters",
+			Alphabet::Printable => "printable",
+			Alphabet::Bytes => "bytes",
+		};
+	}
+
+	/// Plural of the symbols for messages
+	pub fn symbols(&self) -> &'static str {
+		return match self {
+			Alphabet::Letters => "letters",
+			Alphabet::Printable => "printable characters",
+			Alphabet::Bytes => "bytes",
+		};
+	}
+
+	/// Index of a word in the alphabet, None for every other word
+	pub fn index(&self, value: u128) -> Option<u8> {
+		return match self {
+			Alphabet::Letters => letter_index(value),
+			Alphabet::Printable if (0x20..0x7f).contains(&value) => Some((value - 0x20) as u8),
+			Alphabet::Bytes if value < 0x100 => Some(value as u8),
+			_ => None,
+		};
+	}
+
+	/// Word at an index of the alphabet, upper case for the letters. The
+	/// index wraps around the size, to show a key as text.
+	pub fn word(&self, index: usize) -> u8 {
+		let index = (index % self.size()) as u8;
+		return match self {
+			Alphabet::Letters => b'A' + index,
+			Alphabet::Printable => b' ' + index,
+			Alphabet::Bytes => index,
+		};
+	}
+
+	/// Indices of the words which are in the alphabet, all other words are
+	/// dropped
+	pub fn indices<T: Copy + Into<u128>>(&self, vec: &[T]) -> Vec<u8> {
+		return vec.iter().filter_map(|w| self.index((*w).into())).collect();
+	}
+
+	/// Longest n-gram with a table, MAX_NGRAM for the letters, trigrams for
+	/// printable ASCII and bigrams for bytes
+	pub fn max_ngram(&self) -> usize {
+		return (1..=MAX_NGRAM).rev()
+			.find(|n| self.size().pow(*n as u32) <= MAX_TABLE_SIZE)
+			.unwrap_or(1);
+	}
+}
+
+/// Log10 probabilities of every n-gram of an alphabet
+#[derive(PartialEq, Debug, Clone)]
+pub struct NgramTable {
+	n: usize,
+	size: usize,
+	counts: Vec<u64>,
+	log_probabilities: Vec<f64>,
+}
+
+impl NgramTable {
+	/// Count every n-gram of indices of the alphabet, n has to be between 1
+	/// and the max_ngram of the alphabet
+	pub fn from_indices(alphabet: Alphabet, indices: &[u8], n: usize)
+			-> Result<NgramTable, io::Error> {
+		if n == 0 || n > alphabet.max_ngram() {
+			return Err(io::Error::other(
+				format!("N-gram size needs to be between 1 and {}", alphabet.max_ngram())));
+		}
+		let mut counts: Vec<u64> = vec![0; alphabet.size().pow(n as u32)];
+		for g in indices.windows(n) {
+			counts[ngram_index(g, alphabet.size())] += 1;
+		}
+		return NgramTable::from_counts(alphabet, n, counts);
+	}
+
+	/// Count every n-gram of letter indices, n has to be between 1 and
+	/// MAX_NGRAM
+	pub fn from_letters(letters: &[u8], n: usize) -> Result<NgramTable, io::Error> {
+		return NgramTable::from_indices(Alphabet::Letters, letters, n);
+	}
+
+	/// Count every n-gram of the letters of a text, case and every other
+	/// character are ignored
+	pub fn from_text(text: &[u8], n: usize) -> Result<NgramTable, io::Error> {
+		return NgramTable::from_letters(&letter_indices(text), n);
+	}
+
+	/// Table of counts of every n-gram, indexed by the symbols as digits of
+	/// base alphabet size with the first symbol as the most significant
+	pub fn from_counts(alphabet: Alphabet, n: usize, counts: Vec<u64>)
+			-> Result<NgramTable, io::Error> {
+		if n == 0 || n > alphabet.max_ngram() || counts.len() != alphabet.size().pow(n as u32) {
+			return Err(io::Error::other("Invalid n-gram table"));
+		}
+		let total = std::cmp::max(counts.iter().sum::<u64>(), 1) as f64;
+		let log_probabilities = counts.iter().map(|c| match c {
+			0 => (NGRAM_FLOOR / total).log10(),
+			c => (*c as f64 / total).log10(),
+		}).collect();
+		return Ok(NgramTable {
+			n: n,
+			size: alphabet.size(),
+			counts: counts,
+			log_probabilities: log_probabilities,
+		});
+	}
+
+	pub fn n(&self) -> usize {
+		return self.n;
+	}
+
+	pub fn counts(&self) -> &Vec<u64> {
+		return &self.counts;
+	}
+
+	/// Log10 probability of one n-gram of symbol indices
+	pub fn log_probability(&self, ngram: &[u8]) -> f64 {
+		return self.log_probabilities[ngram_index(ngram, self.size)];
+	}
+
+	/// Sum of the log probabilities of every n-gram of symbol indices,
+	/// higher is more likely
+	pub fn score(&self, indices: &[u8]) -> f64 {
+		return indices.windows(self.n).map(|g| self.log_probability(g)).sum();
+	}
+}
+
+fn ngram_index(g: &[u8], size: usize) -> usize {
+	return g.iter().fold(0, |i, l| i * size + usize::from(*l));
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::scoring::ngrams::Alphabet;
+	use crate::scoring::ngrams::NgramTable;
+	use crate::scoring::ngrams::letter_index;
+	use crate::scoring::ngrams::letter_indices;
+
+	#[test]
+	fn letter_indices_test() {
+		assert_eq!(letter_index(u128::from(b'a')), Some(0));
+		assert_eq!(letter_index(u128::from(b'Z')), Some(25));
+		assert_eq!(letter_index(0x141), None);
+		assert_eq!(letter_indices(&b"Hi, 2 you"[..]), vec![7, 8, 24, 14, 20]);
+	}
+
+	#[test]
+	fn ngram_table_score() {
+		let quadgrams = NgramTable::from_text(b"That, THAT! xyz", 4).unwrap();
+		// THAT twice of the 8 quadgrams of THATTHATXYZ
+		assert!((quadgrams.score(&[19, 7, 0, 19]) - 0.25_f64.log10()).abs() < 1e-9);
+		assert!((quadgrams.score(&[0, 0, 0, 0]) - 0.00125_f64.log10()).abs() < 1e-9);
+		assert_eq!(quadgrams.score(&[19, 7, 0]), 0.0);
+		let monograms = NgramTable::from_text(b"abba", 1).unwrap();
+		assert!((monograms.log_probability(&[1]) - 0.5_f64.log10()).abs() < 1e-9);
+		assert!((monograms.score(&[0, 1]) - 0.25_f64.log10()).abs() < 1e-9);
+		assert_eq!(monograms.counts()[..3], [2, 2, 0]);
+	}
+
+	#[test]
+	fn ngram_table_errors() {
+		let err = NgramTable::from_text(b"abc", 5).unwrap_err();
+		assert_eq!(err.to_string(), "N-gram size needs to be between 1 and 4");
+		let err = NgramTable::from_counts(Alphabet::Letters, 2, vec![0; 26]).unwrap_err();
+		assert_eq!(err.to_string(), "Invalid n-gram table");
+		let err = NgramTable::from_indices(Alphabet::Bytes, &[1, 2, 3], 3).unwrap_err();
+		assert_eq!(err.to_string(), "N-gram size needs to be between 1 and 2");
+	}
+
+	#[test]
+	fn ngram_alphabet() {
+		assert_eq!(Alphabet::Letters.indices(&b"Hi, 2 you"[..]), vec![7, 8, 24, 14, 20]);
+		assert_eq!(Alphabet::Printable.indices(&b"Hi,\n~"[..]), vec![40, 73, 12, 94]);
+		assert_eq!(Alphabet::Bytes.indices(&[0_u16, 0xff, 0x100]), vec![0, 255]);
+		assert_eq!(Alphabet::Letters.max_ngram(), 4);
+		assert_eq!(Alphabet::Printable.max_ngram(), 3);
+		assert_eq!(Alphabet::Bytes.max_ngram(), 2);
+		assert_eq!(Alphabet::Letters.word(3), b'D');
+		assert_eq!(Alphabet::Letters.word(29), b'D');
+		assert_eq!(Alphabet::Printable.word(1), b'!');
+		assert_eq!(Alphabet::Bytes.word(200), 200);
+		let bigrams = NgramTable::from_indices(Alphabet::Bytes, &[0, 255, 0, 255], 2).unwrap();
+		assert!((bigrams.log_probability(&[0, 255]) - (2.0_f64 / 3.0).log10()).abs() < 1e-9);
+	}
+}
@@ -0,0 +1,178 @@
+use std::io::Write;
+use std::io;
+use super::language_model::LanguageModel;
+use super::ngrams::MAX_NGRAM;
+use crate::output::OutputFormat;
+use crate::output::write_json_float;
+use crate::output::write_json_string;
+
+const NGRAM_NAMES: [&str; MAX_NGRAM] = ["monograms", "bigrams", "trigrams", "quadgrams"];
+
+/// Average log10 probability per n-gram of the input in one language, for
+/// the monograms up to the longest n-grams of the alphabet of the model
+#[derive(PartialEq, Debug, Clone)]
+pub struct PlaintextScore {
+	pub language: String,
+	pub scores: Vec<f64>,
+}
+
+/// Rate how much the input looks like plaintext of every language model,
+/// ranked by the score of the longest n-grams, best first. The models need
+/// the same alphabet, the scores of different alphabets are not comparable.
+pub fn plaintext_score<
+	T: Copy + Into<u128>
+>(vec: &[T], models: &[&LanguageModel]) -> Result<Vec<PlaintextScore>, io::Error> {
+	if models.iter().any(|m| m.alphabet != models[0].alphabet) {
+		return Err(io::Error::other("Language models of different alphabets can not be compared"));
+	}
+	let mut result: Vec<PlaintextScore> = Vec::with_capacity(models.len());
+	for model in models {
+		let symbols = model.alphabet.indices(vec);
+		let max_ngram = model.alphabet.max_ngram();
+		if symbols.len() < max_ngram {
+			return Err(io::Error::other(format!(
+				"Scoring needs at least {} {}", max_ngram, model.alphabet.symbols())));
+		}
+		let mut scores: Vec<f64> = Vec::with_capacity(max_ngram);
+		for n in 1..=max_ngram {
+			let count = symbols.len() + 1 - n;
+			scores.push(model.ngram(n)?.score(&symbols) / count as f64);
+		}
+		result.push(PlaintextScore { language: model.name.clone(), scores: scores });
+	}
+	result.sort_by(|a, b| b.scores[b.scores.len() - 1].total_cmp(&a.scores[a.scores.len() - 1]));
+	return Ok(result);
+}
+
+/// Write the result of plaintext_score in the given format
+pub fn print_plaintext_score_result<
+	W: Write
+>(result: &Vec<PlaintextScore>, format: &OutputFormat, out: &mut W)
+		-> Result<(), io::Error> {
+	let names = &NGRAM_NAMES[..result.iter().map(|r| r.scores.len()).max().unwrap_or(MAX_NGRAM)];
+	match format {
+		OutputFormat::Text => {
+			writeln!(out, "Average log10 probability of the {}", names.join(", "))?;
+			for r in result {
+				write!(out, "{}:", r.language)?;
+				for s in r.scores.iter() {
+					write!(out, " {:.4}", s)?;
+				}
+				writeln!(out)?;
+			}
+			if let Some(best) = result.first() {
+				writeln!(out, "Best match: {}", best.language)?;
+			}
+		},
+		OutputFormat::Json => {
+			write!(out, "{{\"scores\":[")?;
+			for (k, r) in result.iter().enumerate() {
+				if k > 0 {
+					write!(out, ",")?;
+				}
+				write!(out, "{{\"language\":")?;
+				write_json_string(&r.language, out)?;
+				for (name, s) in names.iter().zip(r.scores.iter()) {
+					write!(out, ",\"{}\":", name)?;
+					write_json_float(*s, out)?;
+				}
+				write!(out, "}}")?;
+			}
+			write!(out, "]")?;
+			if let Some(best) = result.first() {
+				write!(out, ",\"best_match\":")?;
+				write_json_string(&best.language, out)?;
+			}
+			writeln!(out, "}}")?;
+		},
+		OutputFormat::Csv => {
+			writeln!(out, "language,{}", names.join(","))?;
+			for r in result {
+				write!(out, "{}", r.language)?;
+				for s in r.scores.iter() {
+					write!(out, ",{}", s)?;
+				}
+				writeln!(out)?;
+			}
+		},
+	};
+	return Ok(());
+}
+
+#[cfg(test)]
+mod tests {
+	use std::io::Write;
+	use crate::output::OutputFormat;
+	use crate::scoring::language_model::LanguageModel;
+	use crate::scoring::language_model::builtin_models;
+	use crate::scoring::ngrams::Alphabet;
+	use crate::scoring::plaintext_score::PlaintextScore;
+	use crate::scoring::plaintext_score::plaintext_score;
+	use crate::scoring::plaintext_score::print_plaintext_score_result;
+
+	#[test]
+	fn plaintext_score_languages() {
+		let models: Vec<&LanguageModel> = builtin_models().iter().collect();
+		let german = b"Am Abend sassen wir noch lange zusammen und sprachen ueber die Reise.";
+		assert_eq!(plaintext_score(&german[..], &models).unwrap()[0].language, "german");
+		let french = b"Nous sommes restes longtemps ensemble a parler du voyage ce soir.";
+		assert_eq!(plaintext_score(&french[..], &models).unwrap()[0].language, "french");
+		let spanish = b"Por la noche estuvimos mucho tiempo juntos hablando del viaje.";
+		assert_eq!(plaintext_score(&spanish[..], &models).unwrap()[0].language, "spanish");
+		let english = b"In the evening we sat together for a long time and talked about the trip.";
+		assert_eq!(plaintext_score(&english[..], &models).unwrap()[0].language, "english");
+	}
+
+	#[test]
+	fn plaintext_score_values() {
+		let model = LanguageModel::from_text("ab", b"abab");
+		let models = vec![&model];
+		let result = plaintext_score(b"abab", &models).unwrap();
+		// a and b are half of the monograms, ab two of the three bigrams
+		assert!((result[0].scores[0] - 0.5_f64.log10()).abs() < 1e-9);
+		assert!((result[0].scores[1] - (2.0 * (2.0_f64 / 3.0).log10() + (1.0_f64 / 3.0).log10()) / 3.0).abs() < 1e-9);
+		assert!((result[0].scores[3]).abs() < 1e-9);
+		let err = plaintext_score(&b"a b!c"[..], &models).unwrap_err();
+		assert_eq!(err.to_string(), "Scoring needs at least 4 letters");
+		// Bytes models score every byte with monograms and bigrams
+		let model = LanguageModel::from_words("ab", Alphabet::Bytes, b"a a ");
+		let result = plaintext_score(b"a a ", &[&model]).unwrap();
+		assert_eq!(result[0].scores.len(), 2);
+		assert!((result[0].scores[0] - 0.5_f64.log10()).abs() < 1e-9);
+		let err = plaintext_score(b"a", &[&model]).unwrap_err();
+		assert_eq!(err.to_string(), "Scoring needs at least 2 bytes");
+		let letters = LanguageModel::from_text("ab", b"abab");
+		let err = plaintext_score(b"a a ", &[&letters, &model]).unwrap_err();
+		assert_eq!(err.to_string(), "Language models of different alphabets can not be compared");
+	}
+
+	#[test]
+	fn print_plaintext_score_result_test() {
+		let result = vec![
+			PlaintextScore { language: "english".to_string(), scores: vec![-1.25, -2.5, -3.0, -4.5] },
+			PlaintextScore { language: "german".to_string(), scores: vec![-1.5, -2.75, -3.5, -5.0] },
+		];
+		let mut out = Vec::new();
+		print_plaintext_score_result(&result, &OutputFormat::Text, &mut out).unwrap();
+		print_plaintext_score_result(&result, &OutputFormat::Json, &mut out).unwrap();
+		print_plaintext_score_result(&result, &OutputFormat::Csv, &mut out).unwrap();
+		let mut expected = Vec::new();
+		writeln!(
+			expected,
+			"Average log10 probability of the monograms, bigrams, trigrams, quadgrams\n\
+			english: -1.2500 -2.5000 -3.0000 -4.5000\n\
+			german: -1.5000 -2.7500 -3.5000 -5.0000\n\
+			Best match: english").unwrap();
+		writeln!(
+			expected,
+			"{{\"scores\":[{{\"language\":\"english\",\"monograms\":-1.25,\"bigrams\":-2.5,\
+			\"trigrams\":-3,\"quadgrams\":-4.5}},{{\"language\":\"german\",\"monograms\":-1.5,\
+			\"bigrams\":-2.75,\"trigrams\":-3.5,\"quadgrams\":-5}}],\"best_match\":\"english\"}}").unwrap();
+		writeln!(
+			expected,
+			"language,monograms,bigrams,trigrams,quadgrams\n\
+			english,-1.25,-2.5,-3,-4.5\n\
+			german,-1.5,-2.75,-3.5,-5").unwrap();
+		assert_eq!(String::from_utf8(out).unwrap(), String::from_utf8(expected).unwrap());
+	}
+}
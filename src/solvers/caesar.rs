@@ -0,0 +1,192 @@
+use std::convert::TryFrom;
+use std::ops::AddAssign;
+use std::hash::Hash;
+use std::io::Write;
+use std::io;
+use crate::analytics::text_chi_squared;
+use crate::languages::ENGLISH;
+use crate::output::OutputFormat;
+use crate::output::words_to_text;
+use crate::output::write_json_float;
+use crate::output::write_json_string;
+use crate::output::write_csv_string;
+use crate::scoring::Alphabet;
+
+/// Decryption of the input with one key
+#[derive(PartialEq, Debug, Clone)]
+pub struct CaesarCandidate<T> {
+	pub key: usize,
+	/// Chi-squared statistic against english, lower is better
+	pub score: f64,
+	pub plaintext: Vec<T>,
+}
+
+fn shift_range(value: u128, start: u8, size: usize, shift: usize) -> u128 {
+	let start = u128::from(start);
+	return start + (value - start + shift as u128) % size as u128;
+}
+
+/// Shift one word by shift positions in the alphabet
+pub fn shift_word<
+	T: Copy + Into<u128> + TryFrom<u128>
+>(word: T, shift: usize, alphabet: &Alphabet) -> T {
+	let v: u128 = word.into();
+	let shifted = match alphabet {
+		Alphabet::Letters => match v {
+			0x41..=0x5a => shift_range(v, b'A', 26, shift),
+			0x61..=0x7a => shift_range(v, b'a', 26, shift),
+			_ => v,
+		},
+		Alphabet::Printable => match v {
+			0x20..=0x7e => shift_range(v, b' ', 95, shift),
+			_ => v,
+		},
+		Alphabet::Bytes => match v {
+			0x00..=0xff => shift_range(v, 0, 256, shift),
+			_ => v,
+		},
+	};
+	return match T::try_from(shifted) {
+		Ok(t) => t,
+		Err(_) => word,
+	};
+}
+
+/// Decrypt the input with the key, the inverse of shifting by key
+pub fn caesar_decrypt<
+	T: Copy + Into<u128> + TryFrom<u128>
+>(vec: &[T], key: usize, alphabet: &Alphabet) -> Vec<T> {
+	let shift = (alphabet.size() - key % alphabet.size()) % alphabet.size();
+	return vec.iter().map(|w| shift_word(*w, shift, alphabet)).collect();
+}
+
+/// Try every key of the alphabet, best top candidates first
+pub fn caesar<
+	T: Copy + AddAssign + Eq + Hash + From<u8> + Into<u128> + TryFrom<u128>
+>(vec: &[T], alphabet: &Alphabet, top: usize) -> Vec<CaesarCandidate<T>> {
+	let mut result: Vec<CaesarCandidate<T>> = Vec::with_capacity(alphabet.size());
+	for key in 0..alphabet.size() {
+		let plaintext = caesar_decrypt(vec, key, alphabet);
+		result.push(CaesarCandidate {
+			key: key,
+			score: text_chi_squared(&plaintext, &ENGLISH),
+			plaintext: plaintext,
+		});
+	}
+	result.sort_by(|a, b| a.score.total_cmp(&b.score));
+	result.truncate(top);
+	return result;
+}
+
+/// Write the result of caesar in the given format
+pub fn print_caesar_result<
+	T: Copy + Into<u128>, W: Write
+>(result: &Vec<CaesarCandidate<T>>, format: &OutputFormat, out: &mut W)
+		-> Result<(), io::Error> {
+	match format {
+		OutputFormat::Text => {
+			writeln!(out, "Candidates: {}", result.len())?;
+			for c in result {
+				writeln!(
+					out, "{}: {:.6}: {}", c.key, c.score, words_to_text(&c.plaintext))?;
+			}
+		},
+		OutputFormat::Json => {
+			write!(out, "{{\"candidates\":[")?;
+			for (k, c) in result.iter().enumerate() {
+				if k > 0 {
+					write!(out, ",")?;
+				}
+				write!(out, "{{\"key\":{},\"score\":", c.key)?;
+				write_json_float(c.score, out)?;
+				write!(out, ",\"plaintext\":")?;
+				write_json_string(&words_to_text(&c.plaintext), out)?;
+				write!(out, "}}")?;
+			}
+			writeln!(out, "]}}")?;
+		},
+		OutputFormat::Csv => {
+			writeln!(out, "key,score,plaintext")?;
+			for c in result {
+				write!(out, "{},{},", c.key, c.score)?;
+				write_csv_string(&words_to_text(&c.plaintext), out)?;
+				writeln!(out)?;
+			}
+		},
+	};
+	return Ok(());
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::solvers::caesar::caesar;
+	use crate::solvers::caesar::caesar_decrypt;
+	use crate::solvers::caesar::shift_word;
+	use crate::solvers::caesar::print_caesar_result;
+	use crate::scoring::Alphabet;
+	use crate::solvers::caesar::CaesarCandidate;
+	use std::io::Write;
+	use crate::output::OutputFormat;
+
+	#[test]
+	fn shift_word_alphabets() {
+		assert_eq!(shift_word(b'Y', 3, &Alphabet::Letters), b'B');
+		assert_eq!(shift_word(b'y', 3, &Alphabet::Letters), b'b');
+		assert_eq!(shift_word(b'!', 3, &Alphabet::Letters), b'!');
+		assert_eq!(shift_word(b'~', 1, &Alphabet::Printable), b' ');
+		assert_eq!(shift_word(b'\n', 1, &Alphabet::Printable), b'\n');
+		assert_eq!(shift_word(0xff_u8, 2, &Alphabet::Bytes), 0x01);
+		assert_eq!(shift_word(0x1ff_u16, 2, &Alphabet::Bytes), 0x1ff);
+	}
+
+	#[test]
+	fn caesar_decrypt_u8() {
+		assert_eq!(
+			caesar_decrypt(&b"Khoor, Zruog!"[..], 3, &Alphabet::Letters),
+			b"Hello, World!");
+		assert_eq!(
+			caesar_decrypt(&b"abc"[..], 0, &Alphabet::Bytes),
+			b"abc");
+	}
+
+	#[test]
+	fn caesar_letters() {
+		let ciphertext = caesar_decrypt(
+			&b"It was the best of times, it was the worst of times"[..],
+			26 - 7, &Alphabet::Letters);
+		let result = caesar(&ciphertext, &Alphabet::Letters, 3);
+		assert_eq!(result.len(), 3);
+		assert_eq!(result[0].key, 7);
+		assert_eq!(
+			result[0].plaintext,
+			b"It was the best of times, it was the worst of times");
+		assert!(result[0].score <= result[1].score);
+	}
+
+	#[test]
+	fn caesar_bytes_u16() {
+		let plaintext: Vec<u16> = b"the quick brown fox jumps over the lazy dog"
+			.iter().map(|b| u16::from(*b)).collect();
+		let ciphertext = caesar_decrypt(&plaintext, 256 - 200, &Alphabet::Bytes);
+		let result = caesar(&ciphertext, &Alphabet::Bytes, 1);
+		assert_eq!(result[0].key, 200);
+		assert_eq!(result[0].plaintext, plaintext);
+	}
+
+	#[test]
+	fn print_caesar_result_test() {
+		let result = vec![CaesarCandidate { key: 3, score: 1.5, plaintext: b"a\"b".to_vec() }];
+		let mut out = Vec::new();
+		print_caesar_result(&result, &OutputFormat::Text, &mut out).unwrap();
+		print_caesar_result(&result, &OutputFormat::Json, &mut out).unwrap();
+		print_caesar_result(&result, &OutputFormat::Csv, &mut out).unwrap();
+		let mut expected = Vec::new();
+		writeln!(expected, "Candidates: 1\n3: 1.500000: a\"b").unwrap();
+		writeln!(
+			expected,
+			"{{\"candidates\":[{{\"key\":3,\"score\":1.5,\"plaintext\":\"a\\\"b\"}}]}}"
+		).unwrap();
+		writeln!(expected, "key,score,plaintext\n3,1.5,\"a\"\"b\"").unwrap();
+		assert_eq!(out, expected);
+	}
+}
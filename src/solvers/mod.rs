@@ -0,0 +1,56 @@
+//! Methods to decrypt classical ciphers and the functions to write their
+//! results
+
+mod random;
+
+mod caesar;
+pub use caesar::CaesarCandidate;
+pub use caesar::shift_word;
+pub use caesar::caesar_decrypt;
+pub use caesar::caesar;
+pub use caesar::print_caesar_result;
+
+mod vigenere;
+pub use vigenere::VigenereResult;
+pub use vigenere::vigenere_key_length;
+pub use vigenere::vigenere_decrypt;
+pub use vigenere::vigenere;
+pub use vigenere::print_vigenere_result;
+
+mod xor;
+pub use xor::KeyLengthDistance;
+pub use xor::XorCandidate;
+pub use xor::XorResult;
+pub use xor::xor_decrypt;
+pub use xor::xor_key_lengths;
+pub use xor::xor_single_keys;
+pub use xor::printable_ratio;
+pub use xor::xor_single;
+pub use xor::print_xor_single_result;
+pub use xor::xor_repeating;
+pub use xor::print_xor_repeating_result;
+
+mod substitution;
+pub use substitution::SubstitutionResult;
+pub use substitution::substitution_decrypt;
+pub use substitution::substitution;
+pub use substitution::print_substitution_result;
+
+mod transposition;
+pub use transposition::TranspositionDetection;
+pub use transposition::TranspositionCandidate;
+pub use transposition::transposition_detection;
+pub use transposition::print_transposition_detection;
+pub use transposition::columnar_encrypt;
+pub use transposition::columnar_decrypt;
+pub use transposition::columnar_transposition;
+pub use transposition::print_transposition_result;
+
+mod rail_fence;
+pub use rail_fence::Route;
+pub use rail_fence::RouteCandidate;
+pub use rail_fence::route_order;
+pub use rail_fence::route_encrypt;
+pub use rail_fence::route_decrypt;
+pub use rail_fence::rail_fence;
+pub use rail_fence::print_rail_fence_result;
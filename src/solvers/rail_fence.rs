@@ -0,0 +1,339 @@
+use std::io::Write;
+use std::io;
+use crate::output::OutputFormat;
+use crate::output::words_to_text;
+use crate::output::write_json_float;
+use crate::output::write_json_string;
+use crate::output::write_csv_string;
+use crate::scoring::Scorer;
+use crate::scoring::letter_indices;
+
+/// Order in which the plaintext is read to get the ciphertext. For the
+/// routes the plaintext is written row by row into a grid of the given
+/// number of columns, the last row may be incomplete. Reading every column
+/// downwards is a columnar transposition with the identity key, which the
+/// transposition solver already covers.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Route {
+	/// Number of rails and offset of the first word in the zigzag
+	RailFence(usize, usize),
+	/// Columns alternately downwards and upwards
+	AlternatingColumns(usize),
+	/// Rows alternately to the right and to the left
+	AlternatingRows(usize),
+	/// Clockwise spiral from the top left corner inwards
+	Spiral(usize),
+}
+
+impl Route {
+	pub fn name(&self) -> &'static str {
+		return match self {
+			Route::RailFence(_, _) => "rail_fence",
+			Route::AlternatingColumns(_) => "alternating_columns",
+			Route::AlternatingRows(_) => "alternating_rows",
+			Route::Spiral(_) => "spiral",
+		};
+	}
+
+	/// Number of rails or columns
+	pub fn size(&self) -> usize {
+		return match self {
+			Route::RailFence(rails, _) => *rails,
+			Route::AlternatingColumns(c) | Route::AlternatingRows(c) | Route::Spiral(c) => *c,
+		};
+	}
+
+	pub fn offset(&self) -> usize {
+		return match self {
+			Route::RailFence(_, offset) => *offset,
+			_ => 0,
+		};
+	}
+}
+
+fn rail_fence_order(len: usize, rails: usize, offset: usize) -> Vec<usize> {
+	let period = 2 * (rails - 1);
+	let rail = |i: usize| {
+		let p = (i + offset) % period;
+		return std::cmp::min(p, period - p);
+	};
+	let mut result: Vec<usize> = (0..len).collect();
+	result.sort_by_key(|i| (rail(*i), *i));
+	return result;
+}
+
+// Row and column of every cell of a grid in the order of a route
+type GridCells = fn(rows: usize, columns: usize) -> Vec<(usize, usize)>;
+
+fn alternating_columns_cells(rows: usize, columns: usize) -> Vec<(usize, usize)> {
+	let mut cells: Vec<(usize, usize)> = Vec::with_capacity(rows * columns);
+	for c in 0..columns {
+		match c % 2 {
+			0 => cells.extend((0..rows).map(|r| (r, c))),
+			_ => cells.extend((0..rows).rev().map(|r| (r, c))),
+		};
+	}
+	return cells;
+}
+
+fn alternating_rows_cells(rows: usize, columns: usize) -> Vec<(usize, usize)> {
+	let mut cells: Vec<(usize, usize)> = Vec::with_capacity(rows * columns);
+	for r in 0..rows {
+		match r % 2 {
+			0 => cells.extend((0..columns).map(|c| (r, c))),
+			_ => cells.extend((0..columns).rev().map(|c| (r, c))),
+		};
+	}
+	return cells;
+}
+
+fn spiral_cells(rows: usize, columns: usize) -> Vec<(usize, usize)> {
+	let mut cells: Vec<(usize, usize)> = Vec::with_capacity(rows * columns);
+	let (mut top, mut bottom, mut left, mut right) = (0, rows, 0, columns);
+	while top < bottom && left < right {
+		cells.extend((left..right).map(|c| (top, c)));
+		cells.extend((top + 1..bottom).map(|r| (r, right - 1)));
+		if top + 1 < bottom {
+			cells.extend((left..right - 1).rev().map(|c| (bottom - 1, c)));
+		}
+		if left + 1 < right {
+			cells.extend((top + 1..bottom - 1).rev().map(|r| (r, left)));
+		}
+		top += 1;
+		bottom -= 1;
+		left += 1;
+		right -= 1;
+	}
+	return cells;
+}
+
+// Positions of the grid read along the route, positions after the end of
+// the plaintext are skipped
+fn grid_order(len: usize, columns: usize, cells: GridCells) -> Vec<usize> {
+	let rows = len.div_ceil(columns);
+	return cells(rows, columns).iter().map(|(r, c)| r * columns + c).filter(|i| *i < len).collect();
+}
+
+/// Positions of the plaintext in the order of the ciphertext, the route
+/// needs at least 2 rails or 1 column
+pub fn route_order(len: usize, route: &Route) -> Result<Vec<usize>, io::Error> {
+	return match route {
+		Route::RailFence(rails, _) if *rails < 2 => {
+			Err(io::Error::other("A rail fence needs at least 2 rails"))
+		},
+		Route::AlternatingColumns(0) | Route::AlternatingRows(0) | Route::Spiral(0) => {
+			Err(io::Error::other("A route needs at least 1 column"))
+		},
+		Route::RailFence(rails, offset) => Ok(rail_fence_order(len, *rails, *offset)),
+		Route::AlternatingColumns(c) => Ok(grid_order(len, *c, alternating_columns_cells)),
+		Route::AlternatingRows(c) => Ok(grid_order(len, *c, alternating_rows_cells)),
+		Route::Spiral(c) => Ok(grid_order(len, *c, spiral_cells)),
+	};
+}
+
+/// Read the plaintext along the route
+pub fn route_encrypt<T: Copy>(vec: &[T], route: &Route) -> Result<Vec<T>, io::Error> {
+	return Ok(route_order(vec.len(), route)?.iter().map(|i| vec[*i]).collect());
+}
+
+/// Undo route_encrypt
+pub fn route_decrypt<T: Copy>(vec: &[T], route: &Route) -> Result<Vec<T>, io::Error> {
+	let mut result: Vec<T> = vec.to_vec();
+	for (w, i) in vec.iter().zip(route_order(vec.len(), route)?) {
+		result[i] = *w;
+	}
+	return Ok(result);
+}
+
+/// Decryption of the input along one route
+#[derive(PartialEq, Debug, Clone)]
+pub struct RouteCandidate<T> {
+	pub route: Route,
+	/// Score of the plaintext letters, higher is better
+	pub score: f64,
+	pub plaintext: Vec<T>,
+}
+
+/// Try every rail fence with 2 to max_size rails and every offset, and the
+/// routes through grids of 2 to max_size columns. The best top
+/// candidates are returned, best first.
+pub fn rail_fence<
+	T: Copy + Into<u128>, S: Scorer
+>(vec: &[T], scorer: &S, max_size: usize, top: usize)
+		-> Result<Vec<RouteCandidate<T>>, io::Error> {
+	let max_size = std::cmp::min(max_size, vec.len().saturating_sub(1));
+	if max_size < 2 {
+		return Err(io::Error::other("Input is too short for a rail fence"));
+	}
+	let mut routes: Vec<Route> = Vec::new();
+	for size in 2..=max_size {
+		routes.extend((0..2 * (size - 1)).map(|offset| Route::RailFence(size, offset)));
+		routes.push(Route::AlternatingColumns(size));
+		routes.push(Route::AlternatingRows(size));
+		routes.push(Route::Spiral(size));
+	}
+	let mut result: Vec<RouteCandidate<T>> = Vec::with_capacity(routes.len());
+	for route in routes {
+		let plaintext = route_decrypt(vec, &route)?;
+		result.push(RouteCandidate {
+			route: route,
+			score: scorer.score(&letter_indices(&plaintext)),
+			plaintext: plaintext,
+		});
+	}
+	result.sort_by(|a, b| b.score.total_cmp(&a.score));
+	result.truncate(top);
+	return Ok(result);
+}
+
+/// Write the result of rail_fence in the given format
+pub fn print_rail_fence_result<
+	T: Copy + Into<u128>, W: Write
+>(result: &Vec<RouteCandidate<T>>, format: &OutputFormat, out: &mut W)
+		-> Result<(), io::Error> {
+	match format {
+		OutputFormat::Text => {
+			writeln!(out, "Candidates: {}", result.len())?;
+			for c in result {
+				writeln!(
+					out, "{} {} {}: {:.6}: {}", c.route.name(), c.route.size(), c.route.offset(),
+					c.score, words_to_text(&c.plaintext))?;
+			}
+		},
+		OutputFormat::Json => {
+			write!(out, "{{\"candidates\":[")?;
+			for (k, c) in result.iter().enumerate() {
+				if k > 0 {
+					write!(out, ",")?;
+				}
+				write!(
+					out, "{{\"route\":\"{}\",\"size\":{},\"offset\":{},\"score\":",
+					c.route.name(), c.route.size(), c.route.offset())?;
+				write_json_float(c.score, out)?;
+				write!(out, ",\"plaintext\":")?;
+				write_json_string(&words_to_text(&c.plaintext), out)?;
+				write!(out, "}}")?;
+			}
+			writeln!(out, "]}}")?;
+		},
+		OutputFormat::Csv => {
+			writeln!(out, "route,size,offset,score,plaintext")?;
+			for c in result {
+				write!(
+					out, "{},{},{},{},", c.route.name(), c.route.size(), c.route.offset(),
+					c.score)?;
+				write_csv_string(&words_to_text(&c.plaintext), out)?;
+				writeln!(out)?;
+			}
+		},
+	};
+	return Ok(());
+}
+
+#[cfg(test)]
+mod tests {
+	use std::io;
+	use std::io::Write;
+	use crate::languages::ENGLISH;
+	use crate::output::OutputFormat;
+	use crate::scoring::LanguageModel;
+	use crate::solvers::rail_fence::Route;
+	use crate::solvers::rail_fence::RouteCandidate;
+	use crate::solvers::rail_fence::route_order;
+	use crate::solvers::rail_fence::route_encrypt;
+	use crate::solvers::rail_fence::route_decrypt;
+	use crate::solvers::rail_fence::rail_fence;
+	use crate::solvers::rail_fence::print_rail_fence_result;
+
+	#[test]
+	fn rail_fence_encrypt() -> Result<(), io::Error> {
+		let plaintext = b"WEAREDISCOVEREDFLEEATONCE".to_vec();
+		assert_eq!(
+			route_encrypt(&plaintext, &Route::RailFence(3, 0))?,
+			b"WECRLTEERDSOEEFEAOCAIVDEN");
+		// The zigzag starts on the way back up from the bottom rail
+		assert_eq!(route_order(6, &Route::RailFence(3, 2))?, vec![2, 1, 3, 5, 0, 4]);
+		for offset in 0..6 {
+			let route = Route::RailFence(4, offset);
+			assert_eq!(route_decrypt(&route_encrypt(&plaintext, &route)?, &route)?, plaintext);
+		}
+		return Ok(());
+	}
+
+	#[test]
+	fn route_orders() -> Result<(), io::Error> {
+		// 0 1 2
+		// 3 4 5
+		// 6 7
+		assert_eq!(route_order(8, &Route::AlternatingColumns(3))?, vec![0, 3, 6, 7, 4, 1, 2, 5]);
+		assert_eq!(route_order(8, &Route::AlternatingRows(3))?, vec![0, 1, 2, 5, 4, 3, 6, 7]);
+		assert_eq!(route_order(8, &Route::Spiral(3))?, vec![0, 1, 2, 5, 7, 6, 3, 4]);
+		assert_eq!(route_order(6, &Route::Spiral(1))?, vec![0, 1, 2, 3, 4, 5]);
+		let plaintext: Vec<u16> = (0..23).collect();
+		for route in [Route::AlternatingRows(4), Route::AlternatingColumns(5), Route::Spiral(6)] {
+			let ciphertext = route_encrypt(&plaintext, &route)?;
+			let mut sorted = ciphertext.clone();
+			sorted.sort();
+			assert_eq!(sorted, plaintext);
+			assert_eq!(route_decrypt(&ciphertext, &route)?, plaintext);
+		}
+		return Ok(());
+	}
+
+	#[test]
+	fn route_order_errors() {
+		for rails in [0, 1] {
+			let err = route_order(6, &Route::RailFence(rails, 0)).unwrap_err();
+			assert_eq!(err.to_string(), "A rail fence needs at least 2 rails");
+		}
+		for route in [Route::AlternatingColumns(0), Route::AlternatingRows(0), Route::Spiral(0)] {
+			let err = route_order(6, &route).unwrap_err();
+			assert_eq!(err.to_string(), "A route needs at least 1 column");
+		}
+		let err = route_encrypt(&b"abc"[..], &Route::RailFence(1, 0)).unwrap_err();
+		assert_eq!(err.to_string(), "A rail fence needs at least 2 rails");
+		let err = route_decrypt(&b"abc"[..], &Route::Spiral(0)).unwrap_err();
+		assert_eq!(err.to_string(), "A route needs at least 1 column");
+	}
+
+	#[test]
+	fn rail_fence_english() -> Result<(), io::Error> {
+		let model = LanguageModel::from_language(&ENGLISH);
+		let plaintext = b"Meet me at the old bridge after sunset and bring the map".to_vec();
+		for route in [Route::RailFence(5, 3), Route::Spiral(7), Route::AlternatingColumns(4)] {
+			let ciphertext = route_encrypt(&plaintext, &route)?;
+			let result = rail_fence(&ciphertext, &model, 8, 3)?;
+			assert_eq!(result.len(), 3);
+			assert_eq!(result[0].route, route);
+			assert_eq!(result[0].plaintext, plaintext);
+		}
+		let err = rail_fence(&b"ab"[..], &model, 8, 3).unwrap_err();
+		assert_eq!(err.to_string(), "Input is too short for a rail fence");
+		return Ok(());
+	}
+
+	#[test]
+	fn print_rail_fence_result_test() {
+		let result = vec![
+			RouteCandidate { route: Route::RailFence(3, 1), score: -2.5, plaintext: b"Hi".to_vec() },
+			RouteCandidate { route: Route::Spiral(2), score: -3.0, plaintext: b"iH".to_vec() }];
+		let mut out = Vec::new();
+		print_rail_fence_result(&result, &OutputFormat::Text, &mut out).unwrap();
+		print_rail_fence_result(&result, &OutputFormat::Json, &mut out).unwrap();
+		print_rail_fence_result(&result, &OutputFormat::Csv, &mut out).unwrap();
+		let mut expected = Vec::new();
+		writeln!(
+			expected,
+			"Candidates: 2\nrail_fence 3 1: -2.500000: Hi\nspiral 2 0: -3.000000: iH").unwrap();
+		writeln!(
+			expected,
+			"{{\"candidates\":[{{\"route\":\"rail_fence\",\"size\":3,\"offset\":1,\"score\":-2.5,\
+			\"plaintext\":\"Hi\"}},{{\"route\":\"spiral\",\"size\":2,\"offset\":0,\"score\":-3,\
+			\"plaintext\":\"iH\"}}]}}").unwrap();
+		writeln!(
+			expected,
+			"route,size,offset,score,plaintext\nrail_fence,3,1,-2.5,\"Hi\"\n\
+			spiral,2,0,-3,\"iH\"").unwrap();
+		assert_eq!(String::from_utf8(out).unwrap(), String::from_utf8(expected).unwrap());
+	}
+}
@@ -0,0 +1,40 @@
+// A fixed seed keeps the results of the solvers reproducible
+const SEED: u64 = 0x2545f4914f6cdd1d;
+
+/// Xorshift pseudo random numbers for the random restarts of the solvers
+pub(crate) struct Xorshift {
+	state: u64,
+}
+
+impl Xorshift {
+	pub(crate) fn new() -> Xorshift {
+		return Xorshift { state: SEED };
+	}
+
+	pub(crate) fn next(&mut self) -> u64 {
+		self.state ^= self.state << 13;
+		self.state ^= self.state >> 7;
+		self.state ^= self.state << 17;
+		return self.state;
+	}
+
+	/// Random number from 0 to bound - 1
+	pub(crate) fn below(&mut self, bound: usize) -> usize {
+		return (self.next() % bound as u64) as usize;
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::solvers::random::Xorshift;
+
+	#[test]
+	fn xorshift_reproducible() {
+		let mut a = Xorshift::new();
+		let mut b = Xorshift::new();
+		let first: Vec<u64> = (0..4).map(|_| a.next()).collect();
+		assert_eq!(first, (0..4).map(|_| b.next()).collect::<Vec<u64>>());
+		assert_ne!(first[0], first[1]);
+		assert!((0..100).all(|_| a.below(7) < 7));
+	}
+}
@@ -0,0 +1,252 @@
+use std::convert::TryFrom;
+use std::io::Write;
+use std::io;
+use crate::languages::ENGLISH;
+use crate::scoring::LETTERS;
+use crate::scoring::Scorer;
+use crate::scoring::letter_index;
+use crate::scoring::letter_indices;
+use crate::output::OutputFormat;
+use crate::output::words_to_text;
+use crate::output::write_json_float;
+use crate::output::write_json_string;
+use crate::output::write_csv_string;
+use super::random::Xorshift;
+
+// Random key swaps before every restart of the hill climbing
+const RESTART_SWAPS: usize = 8;
+
+/// Recovered key and plaintext of a substitution cipher
+#[derive(PartialEq, Debug, Clone)]
+pub struct SubstitutionResult<T> {
+	/// Plaintext letter of every ciphertext letter A to Z
+	pub key: [u8; LETTERS],
+	/// Score of the plaintext letters, higher is better
+	pub score: f64,
+	pub plaintext: Vec<T>,
+}
+
+/// Replace every letter with its plaintext letter of the key, the case is
+/// kept and all other words are unchanged
+pub fn substitution_decrypt<
+	T: Copy + Into<u128> + TryFrom<u128>
+>(vec: &[T], key: &[u8; LETTERS]) -> Vec<T> {
+	return vec.iter().map(|w| {
+		let value: u128 = (*w).into();
+		let plain = match letter_index(value) {
+			None => return *w,
+			Some(i) if value >= u128::from(b'a') => key[usize::from(i)].to_ascii_lowercase(),
+			Some(i) => key[usize::from(i)],
+		};
+		return match T::try_from(u128::from(plain)) {
+			Ok(t) => t,
+			Err(_) => *w,
+		};
+	}).collect();
+}
+
+// Map the most frequent ciphertext letters to the most frequent english
+// letters
+fn frequency_key(letters: &Vec<u8>) -> [u8; LETTERS] {
+	let mut counts = [0_usize; LETTERS];
+	for l in letters {
+		counts[usize::from(*l)] += 1;
+	}
+	let mut cipher_order: Vec<usize> = (0..LETTERS).collect();
+	cipher_order.sort_by_key(|i| std::cmp::Reverse(counts[*i]));
+	let mut plain_order: Vec<usize> = (0..LETTERS).collect();
+	plain_order.sort_by(
+		|a, b| ENGLISH.letter_frequencies[*b].total_cmp(&ENGLISH.letter_frequencies[*a]));
+	let mut key = [0_u8; LETTERS];
+	for (c, p) in cipher_order.iter().zip(plain_order.iter()) {
+		key[*c] = b'A' + *p as u8;
+	}
+	return key;
+}
+
+fn letters_score<S: Scorer>(letters: &Vec<u8>, key: &[u8; LETTERS], scorer: &S,
+                            buf: &mut Vec<u8>) -> f64 {
+	buf.clear();
+	buf.extend(letters.iter().map(|l| key[usize::from(*l)] - b'A'));
+	return scorer.score(buf);
+}
+
+// Swap two plaintext letters of the key as long as the score improves
+fn hill_climb<S: Scorer>(letters: &Vec<u8>, key: &mut [u8; LETTERS], scorer: &S) -> f64 {
+	let mut buf: Vec<u8> = Vec::with_capacity(letters.len());
+	let mut best = letters_score(letters, key, scorer, &mut buf);
+	let mut improved = true;
+	while improved {
+		improved = false;
+		for i in 0..LETTERS {
+			for j in i + 1..LETTERS {
+				key.swap(i, j);
+				let score = letters_score(letters, key, scorer, &mut buf);
+				if score > best {
+					best = score;
+					improved = true;
+				} else {
+					key.swap(i, j);
+				}
+			}
+		}
+	}
+	return best;
+}
+
+/// Solve a monoalphabetic substitution of the letters A to Z by hill
+/// climbing on the score of the plaintext letters. The first key maps the letters by their
+/// frequency ranking, every restart randomly changes the best key.
+pub fn substitution<
+	T: Copy + Into<u128> + TryFrom<u128>, S: Scorer
+>(vec: &[T], scorer: &S, restarts: usize)
+		-> Result<SubstitutionResult<T>, io::Error> {
+	let letters = letter_indices(vec);
+	if letters.len() < 4 {
+		return Err(io::Error::other("Substitution needs at least 4 letters"));
+	}
+	let mut best_key = frequency_key(&letters);
+	let mut best_score = hill_climb(&letters, &mut best_key, scorer);
+	let mut random = Xorshift::new();
+	for _ in 0..restarts {
+		let mut key = best_key;
+		for _ in 0..RESTART_SWAPS {
+			let i = random.below(LETTERS);
+			let j = random.below(LETTERS);
+			key.swap(i, j);
+		}
+		let score = hill_climb(&letters, &mut key, scorer);
+		if score > best_score {
+			best_score = score;
+			best_key = key;
+		}
+	}
+	return Ok(SubstitutionResult {
+		key: best_key,
+		score: best_score,
+		plaintext: substitution_decrypt(vec, &best_key),
+	});
+}
+
+/// Write the result of substitution in the given format
+pub fn print_substitution_result<
+	T: Copy + Into<u128>, W: Write
+>(result: &SubstitutionResult<T>, format: &OutputFormat, out: &mut W)
+		-> Result<(), io::Error> {
+	let key = String::from_utf8_lossy(&result.key);
+	let plaintext = words_to_text(&result.plaintext);
+	match format {
+		OutputFormat::Text => {
+			writeln!(out, "Ciphertext: ABCDEFGHIJKLMNOPQRSTUVWXYZ")?;
+			writeln!(out, "Plaintext:  {}", key)?;
+			writeln!(out, "Score: {:.6}", result.score)?;
+			writeln!(out, "Text: {}", plaintext)?;
+		},
+		OutputFormat::Json => {
+			write!(out, "{{\"key\":")?;
+			write_json_string(&key, out)?;
+			write!(out, ",\"score\":")?;
+			write_json_float(result.score, out)?;
+			write!(out, ",\"plaintext\":")?;
+			write_json_string(&plaintext, out)?;
+			writeln!(out, "}}")?;
+		},
+		OutputFormat::Csv => {
+			writeln!(out, "key,score,plaintext")?;
+			write!(out, "{},{},", key, result.score)?;
+			write_csv_string(&plaintext, out)?;
+			writeln!(out)?;
+		},
+	};
+	return Ok(());
+}
+
+#[cfg(test)]
+mod tests {
+	use std::io;
+	use crate::languages::ENGLISH;
+	use crate::scoring::LanguageModel;
+	use crate::solvers::substitution::SubstitutionResult;
+	use crate::solvers::substitution::frequency_key;
+	use crate::solvers::substitution::substitution_decrypt;
+	use crate::solvers::substitution::substitution;
+	use crate::solvers::substitution::print_substitution_result;
+	use std::io::Write;
+	use crate::output::OutputFormat;
+
+	const KEY: &[u8; 26] = b"QWERTYUIOPASDFGHJKLZXCVBNM";
+
+	fn inverse_key(key: &[u8; 26]) -> [u8; 26] {
+		let mut inverse = [0_u8; 26];
+		for (i, k) in key.iter().enumerate() {
+			inverse[usize::from(k - b'A')] = b'A' + i as u8;
+		}
+		return inverse;
+	}
+
+	#[test]
+	fn substitution_decrypt_case() {
+		assert_eq!(
+			substitution_decrypt(&b"Hello, World!"[..], KEY),
+			b"Itssg, Vgksr!");
+		assert_eq!(substitution_decrypt(&[0x4100_u16, 0x41], KEY), vec![0x4100, 0x51]);
+	}
+
+	#[test]
+	fn frequency_key_ranking() {
+		// E is the most frequent english letter, T the second
+		let key = frequency_key(&vec![3, 3, 3, 0, 0]);
+		assert_eq!(key[3], b'E');
+		assert_eq!(key[0], b'T');
+	}
+
+	#[test]
+	fn substitution_english() -> Result<(), io::Error> {
+		let plaintext = b"Whoever has made a voyage up the Hudson must remember the blue \
+			mountains. They are a dismembered branch of the great Appalachian family, and \
+			are seen away to the west of the river, swelling up to a noble height, and \
+			lording it over the surrounding country. Every change of season, every change \
+			of weather, indeed, every hour of the day, produces some change in the magical \
+			hues and shapes of these mountains, and they are regarded by all the good wives, \
+			far and near, as perfect barometers.".to_vec();
+		let ciphertext = substitution_decrypt(&plaintext, KEY);
+		let model = LanguageModel::from_language(&ENGLISH);
+		let result = substitution(&ciphertext, &model, 20)?;
+		assert_eq!(result.plaintext, plaintext);
+		assert_eq!(substitution_decrypt(&ciphertext, &result.key), plaintext);
+		let inverse = inverse_key(KEY);
+		for l in plaintext.iter().filter(|c| c.is_ascii_alphabetic()) {
+			let c = usize::from(KEY[usize::from(l.to_ascii_uppercase() - b'A')] - b'A');
+			assert_eq!(result.key[c], inverse[c]);
+		}
+		let err = substitution(&b"ab c"[..], &model, 1).unwrap_err();
+		assert_eq!(err.to_string(), "Substitution needs at least 4 letters");
+		return Ok(());
+	}
+
+	#[test]
+	fn print_substitution_result_test() {
+		let result = SubstitutionResult {
+			key: *KEY,
+			score: -12.5,
+			plaintext: b"Hi".to_vec(),
+		};
+		let mut out = Vec::new();
+		print_substitution_result(&result, &OutputFormat::Text, &mut out).unwrap();
+		print_substitution_result(&result, &OutputFormat::Json, &mut out).unwrap();
+		print_substitution_result(&result, &OutputFormat::Csv, &mut out).unwrap();
+		let mut expected = Vec::new();
+		writeln!(
+			expected,
+			"Ciphertext: ABCDEFGHIJKLMNOPQRSTUVWXYZ\n\
+			Plaintext:  QWERTYUIOPASDFGHJKLZXCVBNM\nScore: -12.500000\nText: Hi").unwrap();
+		writeln!(
+			expected,
+			"{{\"key\":\"QWERTYUIOPASDFGHJKLZXCVBNM\",\"score\":-12.5,\"plaintext\":\"Hi\"}}")
+			.unwrap();
+		writeln!(
+			expected,
+			"key,score,plaintext\nQWERTYUIOPASDFGHJKLZXCVBNM,-12.5,\"Hi\"").unwrap();
+		assert_eq!(out, expected);
+	}
+}
@@ -0,0 +1,439 @@
+use std::io::Write;
+use std::io;
+use crate::output::OutputFormat;
+use crate::output::words_to_text;
+use crate::output::write_json_float;
+use crate::output::write_json_string;
+use crate::output::write_csv_string;
+use crate::scoring::LanguageModel;
+use crate::scoring::NgramTable;
+use crate::scoring::Scorer;
+use crate::scoring::letter_index;
+use crate::scoring::letter_indices;
+use super::random::Xorshift;
+
+// Largest difference of the average monogram log probability to the
+// language for letters of the language
+const MONOGRAM_TOLERANCE: f64 = 0.05;
+// Key lengths up to this are searched exhaustively, 7! keys
+const EXHAUSTIVE_KEY_LENGTH: usize = 7;
+// Random restarts of the hill climbing for longer keys
+const RESTARTS: usize = 20;
+
+/// Letter statistics which tell a transposition from other ciphers: the
+/// letters of a transposition are those of the language, but in an order
+/// whose bigrams are no more likely than random pairs of these letters
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct TranspositionDetection {
+	/// Average monogram log10 probability of the letters
+	pub monogram_score: f64,
+	pub expected_monogram_score: f64,
+	/// Average bigram log10 probability minus twice the monogram score,
+	/// positive if neighbouring letters fit together
+	pub bigram_gain: f64,
+	pub expected_bigram_gain: f64,
+	/// Monograms match the language, but bigrams do not
+	pub transposition: bool,
+}
+
+fn table_entropy(table: &NgramTable) -> f64 {
+	let total = std::cmp::max(table.counts().iter().sum::<u64>(), 1) as f64;
+	let mut result = 0.0;
+	let mut ngram = vec![0_u8; table.n()];
+	for (i, c) in table.counts().iter().enumerate() {
+		if *c == 0 {
+			continue;
+		}
+		let mut index = i;
+		for l in ngram.iter_mut().rev() {
+			*l = (index % 26) as u8;
+			index /= 26;
+		}
+		result += (*c as f64 / total) * table.log_probability(&ngram);
+	}
+	return result;
+}
+
+/// Compare the monogram and bigram statistics of the input letters with a
+/// language model
+pub fn transposition_detection<
+	T: Copy + Into<u128>
+>(vec: &[T], model: &LanguageModel) -> Result<TranspositionDetection, io::Error> {
+	let letters = letter_indices(vec);
+	if letters.len() < 2 {
+		return Err(io::Error::other("Transposition detection needs at least 2 letters"));
+	}
+	let monograms = model.ngram(1)?;
+	let bigrams = model.ngram(2)?;
+	let monogram_score = monograms.score(&letters) / letters.len() as f64;
+	let bigram_score = bigrams.score(&letters) / (letters.len() - 1) as f64;
+	let expected_monogram_score = table_entropy(monograms);
+	let expected_bigram_gain = table_entropy(bigrams) - 2.0 * expected_monogram_score;
+	let bigram_gain = bigram_score - 2.0 * monogram_score;
+	return Ok(TranspositionDetection {
+		monogram_score: monogram_score,
+		expected_monogram_score: expected_monogram_score,
+		bigram_gain: bigram_gain,
+		expected_bigram_gain: expected_bigram_gain,
+		transposition: (monogram_score - expected_monogram_score).abs() < MONOGRAM_TOLERANCE &&
+			bigram_gain < expected_bigram_gain / 2.0,
+	});
+}
+
+/// Write the result of transposition_detection in the given format
+pub fn print_transposition_detection<
+	W: Write
+>(result: &TranspositionDetection, format: &OutputFormat, out: &mut W)
+		-> Result<(), io::Error> {
+	match format {
+		OutputFormat::Text => {
+			writeln!(
+				out, "Monogram score: {:.4}, expected: {:.4}",
+				result.monogram_score, result.expected_monogram_score)?;
+			writeln!(
+				out, "Bigram gain: {:.4}, expected: {:.4}",
+				result.bigram_gain, result.expected_bigram_gain)?;
+			writeln!(
+				out, "Transposition: {}",
+				if result.transposition { "likely" } else { "unlikely" })?;
+		},
+		OutputFormat::Json => {
+			write!(out, "{{\"monogram_score\":")?;
+			write_json_float(result.monogram_score, out)?;
+			write!(out, ",\"expected_monogram_score\":")?;
+			write_json_float(result.expected_monogram_score, out)?;
+			write!(out, ",\"bigram_gain\":")?;
+			write_json_float(result.bigram_gain, out)?;
+			write!(out, ",\"expected_bigram_gain\":")?;
+			write_json_float(result.expected_bigram_gain, out)?;
+			writeln!(out, ",\"transposition\":{}}}", result.transposition)?;
+		},
+		OutputFormat::Csv => {
+			writeln!(
+				out, "monogram_score,expected_monogram_score,bigram_gain,\
+				expected_bigram_gain,transposition")?;
+			writeln!(
+				out, "{},{},{},{},{}", result.monogram_score, result.expected_monogram_score,
+				result.bigram_gain, result.expected_bigram_gain, result.transposition)?;
+		},
+	};
+	return Ok(());
+}
+
+/// Recovered key and plaintext of a columnar transposition
+#[derive(PartialEq, Debug, Clone)]
+pub struct TranspositionCandidate<T> {
+	/// Columns in the order they were read, the key length is its length
+	pub key: Vec<usize>,
+	/// Score of the plaintext letters, higher is better
+	pub score: f64,
+	pub plaintext: Vec<T>,
+}
+
+// Number of words of a column when a text of length len is written into
+// rows of key_length words
+fn column_length(len: usize, key_length: usize, column: usize) -> usize {
+	return len / key_length + usize::from(column < len % key_length);
+}
+
+/// Write the input row by row into as many columns as the key is long and
+/// read the columns in the order of the key
+pub fn columnar_encrypt<T: Copy>(vec: &[T], key: &Vec<usize>) -> Vec<T> {
+	let mut result: Vec<T> = Vec::with_capacity(vec.len());
+	for column in key {
+		result.extend(vec.iter().skip(*column).step_by(key.len()));
+	}
+	return result;
+}
+
+/// Undo columnar_encrypt, the last row may be incomplete
+pub fn columnar_decrypt<T: Copy>(vec: &[T], key: &Vec<usize>) -> Vec<T> {
+	let mut result: Vec<T> = vec.to_vec();
+	let mut start = 0;
+	for column in key {
+		let length = column_length(vec.len(), key.len(), *column);
+		for (row, w) in vec[start..start + length].iter().enumerate() {
+			result[row * key.len() + column] = *w;
+		}
+		start += length;
+	}
+	return result;
+}
+
+fn key_score<S: Scorer>(vec: &Vec<Option<u8>>, key: &Vec<usize>, scorer: &S) -> f64 {
+	let letters: Vec<u8> = columnar_decrypt(vec, key).into_iter().flatten().collect();
+	return scorer.score(&letters);
+}
+
+// Lexicographically next permutation, false after the last one
+fn next_permutation(key: &mut Vec<usize>) -> bool {
+	let i = match (1..key.len()).rev().find(|i| key[i - 1] < key[*i]) {
+		Some(i) => i,
+		None => return false,
+	};
+	let j = (i..key.len()).rev().find(|j| key[*j] > key[i - 1]).expect("Larger element exists");
+	key.swap(i - 1, j);
+	key[i..].reverse();
+	return true;
+}
+
+// Swap two columns or move one column to another position of the key as long
+// as the score improves
+fn hill_climb<S: Scorer>(vec: &Vec<Option<u8>>, key: &mut Vec<usize>, scorer: &S) -> f64 {
+	let mut best = key_score(vec, key, scorer);
+	let mut improved = true;
+	while improved {
+		improved = false;
+		for i in 0..key.len() {
+			for j in 0..key.len() {
+				if i == j {
+					continue;
+				}
+				let mut candidates = vec![key.clone(), key.clone()];
+				candidates[0].swap(i, j);
+				let column = candidates[1].remove(i);
+				candidates[1].insert(j, column);
+				for candidate in candidates {
+					let score = key_score(vec, &candidate, scorer);
+					if score > best {
+						best = score;
+						*key = candidate;
+						improved = true;
+					}
+				}
+			}
+		}
+	}
+	return best;
+}
+
+fn best_key<S: Scorer>(vec: &Vec<Option<u8>>, key_length: usize, scorer: &S) -> (Vec<usize>, f64) {
+	let mut key: Vec<usize> = (0..key_length).collect();
+	let mut best = (key.clone(), f64::NEG_INFINITY);
+	if key_length <= EXHAUSTIVE_KEY_LENGTH {
+		loop {
+			let score = key_score(vec, &key, scorer);
+			if score > best.1 {
+				best = (key.clone(), score);
+			}
+			if !next_permutation(&mut key) {
+				return best;
+			}
+		}
+	}
+	let mut random = Xorshift::new();
+	for _ in 0..RESTARTS {
+		// Fisher-Yates shuffle
+		for i in (1..key_length).rev() {
+			key.swap(i, random.below(i + 1));
+		}
+		let score = hill_climb(vec, &mut key, scorer);
+		if score > best.1 {
+			best = (key.clone(), score);
+		}
+	}
+	return best;
+}
+
+/// Solve a columnar transposition for the key lengths 2 to max_key_length,
+/// or only key_length if it is not 0, by searching the column order with
+/// the best score of the plaintext letters. Short keys are searched
+/// exhaustively, longer ones by hill climbing. The best top candidates are
+/// returned, best first.
+pub fn columnar_transposition<
+	T: Copy + Into<u128>, S: Scorer
+>(vec: &[T], scorer: &S, max_key_length: usize, key_length: usize, top: usize)
+		-> Result<Vec<TranspositionCandidate<T>>, io::Error> {
+	if key_length == 0 && max_key_length < 2 {
+		return Err(io::Error::other("Maximum key length needs to be at least 2"));
+	}
+	let key_lengths = match key_length {
+		0 => 2..=std::cmp::min(max_key_length, vec.len() / 2),
+		k => k..=k,
+	};
+	if key_lengths.is_empty() || *key_lengths.end() > vec.len() {
+		return Err(io::Error::other("Input is too short for the key lengths"));
+	}
+	let letters: Vec<Option<u8>> = vec.iter().map(|w| letter_index((*w).into())).collect();
+	let mut result: Vec<TranspositionCandidate<T>> = Vec::with_capacity(key_lengths.clone().count());
+	for k in key_lengths {
+		let (key, score) = best_key(&letters, k, scorer);
+		result.push(TranspositionCandidate {
+			plaintext: columnar_decrypt(vec, &key),
+			key: key,
+			score: score,
+		});
+	}
+	result.sort_by(|a, b| b.score.total_cmp(&a.score));
+	result.truncate(top);
+	return Ok(result);
+}
+
+/// Write the result of columnar_transposition in the given format
+pub fn print_transposition_result<
+	T: Copy + Into<u128>, W: Write
+>(result: &Vec<TranspositionCandidate<T>>, format: &OutputFormat, out: &mut W)
+		-> Result<(), io::Error> {
+	let keys: Vec<String> = result.iter()
+		.map(|c| c.key.iter().map(|k| k.to_string()).collect::<Vec<String>>().join(" "))
+		.collect();
+	match format {
+		OutputFormat::Text => {
+			writeln!(out, "Candidates: {}", result.len())?;
+			for (c, key) in result.iter().zip(keys.iter()) {
+				writeln!(out, "{}: {:.6}: {}", key, c.score, words_to_text(&c.plaintext))?;
+			}
+		},
+		OutputFormat::Json => {
+			write!(out, "{{\"candidates\":[")?;
+			for (k, c) in result.iter().enumerate() {
+				if k > 0 {
+					write!(out, ",")?;
+				}
+				write!(out, "{{\"key_length\":{},\"key\":[{}],\"score\":", c.key.len(),
+				       keys[k].replace(' ', ","))?;
+				write_json_float(c.score, out)?;
+				write!(out, ",\"plaintext\":")?;
+				write_json_string(&words_to_text(&c.plaintext), out)?;
+				write!(out, "}}")?;
+			}
+			writeln!(out, "]}}")?;
+		},
+		OutputFormat::Csv => {
+			writeln!(out, "key_length,key,score,plaintext")?;
+			for (c, key) in result.iter().zip(keys.iter()) {
+				write!(out, "{},{},{},", c.key.len(), key, c.score)?;
+				write_csv_string(&words_to_text(&c.plaintext), out)?;
+				writeln!(out)?;
+			}
+		},
+	};
+	return Ok(());
+}
+
+#[cfg(test)]
+mod tests {
+	use std::io;
+	use std::io::Write;
+	use crate::languages::ENGLISH;
+	use crate::output::OutputFormat;
+	use crate::scoring::LanguageModel;
+	use crate::solvers::transposition::TranspositionCandidate;
+	use crate::solvers::transposition::TranspositionDetection;
+	use crate::solvers::transposition::transposition_detection;
+	use crate::solvers::transposition::print_transposition_detection;
+	use crate::solvers::transposition::columnar_encrypt;
+	use crate::solvers::transposition::columnar_decrypt;
+	use crate::solvers::transposition::next_permutation;
+	use crate::solvers::transposition::columnar_transposition;
+	use crate::solvers::transposition::print_transposition_result;
+
+	const PLAINTEXT: &[u8] = b"The river winds slowly through the valley between green \
+		fields and small villages, and every evening the fishermen bring their boats \
+		back to the old harbour where the market is held on the next morning";
+
+	#[test]
+	fn columnar_encrypt_decrypt() {
+		let key = vec![2, 0, 3, 1];
+		let ciphertext = columnar_encrypt(&b"WEAREDISCOVERED"[..], &key);
+		assert_eq!(ciphertext, b"AIVDWECRRSEEDOE");
+		assert_eq!(columnar_decrypt(&ciphertext, &key), b"WEAREDISCOVERED");
+		assert_eq!(columnar_decrypt(&[1_u16], &vec![1, 0]), vec![1]);
+	}
+
+	#[test]
+	fn next_permutation_order() {
+		let mut key = vec![0, 1, 2];
+		let mut keys = vec![key.clone()];
+		while next_permutation(&mut key) {
+			keys.push(key.clone());
+		}
+		assert_eq!(keys, vec![
+			vec![0, 1, 2], vec![0, 2, 1], vec![1, 0, 2],
+			vec![1, 2, 0], vec![2, 0, 1], vec![2, 1, 0]]);
+	}
+
+	#[test]
+	fn transposition_detection_english() -> Result<(), io::Error> {
+		let model = LanguageModel::from_language(&ENGLISH);
+		let plaintext = PLAINTEXT.to_vec();
+		assert!(!transposition_detection(&plaintext, &model)?.transposition);
+		let ciphertext = columnar_encrypt(&plaintext, &vec![3, 1, 4, 0, 2]);
+		let detection = transposition_detection(&ciphertext, &model)?;
+		assert!(detection.transposition);
+		assert!((detection.monogram_score - detection.expected_monogram_score).abs() < 0.05);
+		// A substitution changes the letters
+		let substituted: Vec<u8> = plaintext.iter().map(|c| match c {
+			b'a'..=b'z' => b'a' + (c - b'a') * 7 % 26,
+			c => *c,
+		}).collect();
+		assert!(!transposition_detection(&substituted, &model)?.transposition);
+		let err = transposition_detection(&b"a"[..], &model).unwrap_err();
+		assert_eq!(err.to_string(), "Transposition detection needs at least 2 letters");
+		return Ok(());
+	}
+
+	#[test]
+	fn columnar_transposition_english() -> Result<(), io::Error> {
+		let model = LanguageModel::from_language(&ENGLISH);
+		let bigrams = model.ngram(2)?;
+		let plaintext = PLAINTEXT.to_vec();
+		let key = vec![3, 1, 4, 0, 2];
+		let ciphertext = columnar_encrypt(&plaintext, &key);
+		let result = columnar_transposition(&ciphertext, bigrams, 6, 0, 3)?;
+		assert_eq!(result.len(), 3);
+		assert_eq!(result[0].key, key);
+		assert_eq!(result[0].plaintext, plaintext);
+		// Hill climbing for keys longer than the exhaustive search
+		let key = vec![5, 2, 7, 0, 3, 6, 1, 4];
+		let ciphertext = columnar_encrypt(&plaintext, &key);
+		let result = columnar_transposition(&ciphertext, bigrams, 0, 8, 1)?;
+		assert_eq!(result[0].key, key);
+		let err = columnar_transposition(&b"abc"[..], bigrams, 6, 0, 3).unwrap_err();
+		assert_eq!(err.to_string(), "Input is too short for the key lengths");
+		let err = columnar_transposition(&ciphertext, bigrams, 1, 0, 3).unwrap_err();
+		assert_eq!(err.to_string(), "Maximum key length needs to be at least 2");
+		return Ok(());
+	}
+
+	#[test]
+	fn print_transposition_result_test() {
+		let detection = TranspositionDetection {
+			monogram_score: -1.25,
+			expected_monogram_score: -1.5,
+			bigram_gain: -0.25,
+			expected_bigram_gain: 0.5,
+			transposition: true,
+		};
+		let result = vec![TranspositionCandidate {
+			key: vec![1, 0],
+			score: -2.5,
+			plaintext: b"Hi".to_vec(),
+		}];
+		let mut out = Vec::new();
+		print_transposition_detection(&detection, &OutputFormat::Text, &mut out).unwrap();
+		print_transposition_result(&result, &OutputFormat::Text, &mut out).unwrap();
+		print_transposition_detection(&detection, &OutputFormat::Json, &mut out).unwrap();
+		print_transposition_result(&result, &OutputFormat::Json, &mut out).unwrap();
+		print_transposition_detection(&detection, &OutputFormat::Csv, &mut out).unwrap();
+		print_transposition_result(&result, &OutputFormat::Csv, &mut out).unwrap();
+		let mut expected = Vec::new();
+		writeln!(
+			expected,
+			"Monogram score: -1.2500, expected: -1.5000\n\
+			Bigram gain: -0.2500, expected: 0.5000\nTransposition: likely\n\
+			Candidates: 1\n1 0: -2.500000: Hi").unwrap();
+		writeln!(
+			expected,
+			"{{\"monogram_score\":-1.25,\"expected_monogram_score\":-1.5,\
+			\"bigram_gain\":-0.25,\"expected_bigram_gain\":0.5,\"transposition\":true}}\n\
+			{{\"candidates\":[{{\"key_length\":2,\"key\":[1,0],\"score\":-2.5,\
+			\"plaintext\":\"Hi\"}}]}}").unwrap();
+		writeln!(
+			expected,
+			"monogram_score,expected_monogram_score,bigram_gain,expected_bigram_gain,\
+			transposition\n-1.25,-1.5,-0.25,0.5,true\n\
+			key_length,key,score,plaintext\n2,1 0,-2.5,\"Hi\"").unwrap();
+		assert_eq!(String::from_utf8(out).unwrap(), String::from_utf8(expected).unwrap());
+	}
+}
@@ -0,0 +1,210 @@
+use std::convert::TryFrom;
+use std::ops::AddAssign;
+use std::hash::Hash;
+use std::io::Write;
+use std::io;
+use crate::analytics::period_sweep;
+use crate::input::filter_input_vec;
+use crate::output::OutputFormat;
+use crate::output::words_to_text;
+use crate::output::write_json_array;
+use crate::output::write_json_string;
+use crate::output::write_csv_list;
+use crate::output::write_csv_string;
+use crate::scoring::Alphabet;
+use super::caesar::caesar;
+use super::caesar::shift_word;
+use super::xor::shortest_key;
+
+// The shortest period with an average column index of coincidence of at
+// least this is the key length, english plaintext has about 0.067 and random
+// letters 0.038. The best period is not used, since the short columns of the
+// multiples of the key length often have a higher index of coincidence.
+const KEY_LENGTH_IC: f64 = 0.055;
+// Share of the best period which is enough if no period reaches KEY_LENGTH_IC
+const KEY_LENGTH_IC_SHARE: f64 = 0.9;
+
+/// Recovered key and plaintext
+#[derive(PartialEq, Debug, Clone)]
+pub struct VigenereResult<T> {
+	/// Shift of every key position
+	pub key: Vec<usize>,
+	pub plaintext: Vec<T>,
+}
+
+/// Estimate the key length from the average index of coincidence of the
+/// columns of every period up to max_period
+pub fn vigenere_key_length<
+	T: Copy + AddAssign + Eq + Hash
+>(vec: &[T], max_period: usize) -> Result<usize, io::Error> {
+	let periods = period_sweep(vec, max_period)?;
+	let mut best: f64 = 0.0;
+	for p in &periods {
+		best = best.max(p.index_of_coincidence);
+	}
+	let threshold = KEY_LENGTH_IC.min(best * KEY_LENGTH_IC_SHARE);
+	for p in &periods {
+		if p.index_of_coincidence >= threshold {
+			return Ok(p.period);
+		}
+	}
+	return Err(io::Error::other("Cannot estimate key length"));
+}
+
+/// Decrypt the input, every word is shifted back by the key at its position
+pub fn vigenere_decrypt<
+	T: Copy + Into<u128> + TryFrom<u128>
+>(vec: &[T], key: &Vec<usize>, alphabet: &Alphabet) -> Vec<T> {
+	let size = alphabet.size();
+	return vec.iter().enumerate().map(|(i, w)| {
+		let shift = (size - key[i % key.len()] % size) % size;
+		return shift_word(*w, shift, alphabet);
+	}).collect();
+}
+
+/// Solve every column of the key length as caesar cipher, the key length is
+/// estimated if it is 0. Every word advances the key, also the ones outside of
+/// the alphabet. A key which repeats itself is reduced to its shortest period.
+pub fn vigenere<
+	T: Copy + AddAssign + Eq + Hash + From<u8> + Into<u128> + TryFrom<u128>
+>(vec: &[T], alphabet: &Alphabet, max_period: usize, key_length: usize)
+		-> Result<VigenereResult<T>, io::Error> {
+	let length = match key_length {
+		0 => vigenere_key_length(vec, max_period)?,
+		l => l,
+	};
+	let mut key: Vec<usize> = Vec::with_capacity(length);
+	for column in 0..length {
+		let column_vec = filter_input_vec(vec, length, column)?;
+		let best = caesar(&column_vec, alphabet, 1);
+		key.push(best.first().map_or(0, |c| c.key));
+	}
+	let key = shortest_key(key);
+	let plaintext = vigenere_decrypt(vec, &key, alphabet);
+	return Ok(VigenereResult { key: key, plaintext: plaintext });
+}
+
+/// Write the result of vigenere in the given format
+pub fn print_vigenere_result<
+	T: Copy + Into<u128>, W: Write
+>(result: &VigenereResult<T>, alphabet: &Alphabet, format: &OutputFormat, out: &mut W)
+		-> Result<(), io::Error> {
+	let key_words: Vec<u128> = result.key.iter().map(
+		|k| u128::from(alphabet.word(*k))).collect();
+	let key_text = words_to_text(&key_words);
+	let plaintext = words_to_text(&result.plaintext);
+	match format {
+		OutputFormat::Text => {
+			writeln!(out, "Key length: {}", result.key.len())?;
+			writeln!(out, "Key: {}", key_text)?;
+			writeln!(out, "Plaintext: {}", plaintext)?;
+		},
+		OutputFormat::Json => {
+			write!(out, "{{\"key_length\":{},\"key\":", result.key.len())?;
+			write_json_array(&result.key, out)?;
+			write!(out, ",\"key_text\":")?;
+			write_json_string(&key_text, out)?;
+			write!(out, ",\"plaintext\":")?;
+			write_json_string(&plaintext, out)?;
+			writeln!(out, "}}")?;
+		},
+		OutputFormat::Csv => {
+			writeln!(out, "key_length,key,key_text,plaintext")?;
+			write!(out, "{},", result.key.len())?;
+			write_csv_list(&result.key, out)?;
+			write!(out, ",")?;
+			write_csv_string(&key_text, out)?;
+			write!(out, ",")?;
+			write_csv_string(&plaintext, out)?;
+			writeln!(out)?;
+		},
+	};
+	return Ok(());
+}
+
+#[cfg(test)]
+mod tests {
+	use std::io;
+	use crate::solvers::vigenere::vigenere;
+	use crate::solvers::vigenere::vigenere_decrypt;
+	use crate::solvers::vigenere::vigenere_key_length;
+	use crate::solvers::vigenere::print_vigenere_result;
+	use crate::solvers::vigenere::VigenereResult;
+	use crate::scoring::Alphabet;
+	use std::io::Write;
+	use crate::output::OutputFormat;
+
+	const PLAINTEXT: &[u8] = b"ITISATRUTHUNIVERSALLYACKNOWLEDGEDTHATASINGLEMANINPOSSESSION\
+		OFAGOODFORTUNEMUSTBEINWANTOFAWIFEHOWEVERLITTLEKNOWNTHEFEELINGSORVIEWS\
+		OFSUCHAMANMAYBEONHISFIRSTENTERINGANEIGHBOURHOODTHISTRUTHISSOWELLFIXED\
+		INTHEMINDSOFTHESURROUNDINGFAMILIESTHATHEISCONSIDEREDTHERIGHTFULPROPERTY\
+		OFSOMEONEOROTHEROFTHEIRDAUGHTERS";
+
+	fn encrypt(key: &Vec<usize>) -> Vec<u8> {
+		let inverse: Vec<usize> = key.iter().map(|k| 26 - k).collect();
+		return vigenere_decrypt(PLAINTEXT, &inverse, &Alphabet::Letters);
+	}
+
+	#[test]
+	fn vigenere_decrypt_u8() {
+		let key = vec![11, 4, 12, 14, 13];
+		assert_eq!(
+			vigenere_decrypt(&b"LXFOPVEFRNHR"[..], &key, &Alphabet::Letters),
+			b"ATTACKATDAWN");
+	}
+
+	#[test]
+	fn vigenere_key_length_u8() -> Result<(), io::Error> {
+		let ciphertext = encrypt(&vec![11, 4, 12, 14, 13]);
+		assert_eq!(vigenere_key_length(&ciphertext, 20)?, 5);
+		return Ok(());
+	}
+
+	#[test]
+	fn vigenere_short_text() -> Result<(), io::Error> {
+		// The short columns of 9 and 12 have a higher index of coincidence
+		// than the key length 3
+		let key = vec![10, 4, 24];
+		let inverse: Vec<usize> = key.iter().map(|k| 26 - k).collect();
+		let plaintext = &PLAINTEXT[91..241];
+		let ciphertext = vigenere_decrypt(plaintext, &inverse, &Alphabet::Letters);
+		assert_eq!(vigenere_key_length(&ciphertext, 20)?, 3);
+		let result = vigenere(&ciphertext, &Alphabet::Letters, 20, 0)?;
+		assert_eq!(result.key, key);
+		assert_eq!(result.plaintext, plaintext);
+		// The key of a multiple of the key length is reduced
+		let ciphertext = encrypt(&vec![11, 4, 12, 14, 13]);
+		let result = vigenere(&ciphertext, &Alphabet::Letters, 20, 10)?;
+		assert_eq!(result.key, vec![11, 4, 12, 14, 13]);
+		return Ok(());
+	}
+
+	#[test]
+	fn vigenere_letters() -> Result<(), io::Error> {
+		let key = vec![2, 8, 15, 7, 4, 17];
+		let ciphertext = encrypt(&key);
+		let result = vigenere(&ciphertext, &Alphabet::Letters, 20, 0)?;
+		assert_eq!(result.key, key);
+		assert_eq!(result.plaintext, PLAINTEXT);
+		let fixed = vigenere(&ciphertext, &Alphabet::Letters, 20, 6)?;
+		assert_eq!(fixed, result);
+		return Ok(());
+	}
+
+	#[test]
+	fn print_vigenere_result_test() {
+		let result = VigenereResult { key: vec![11, 4], plaintext: b"AB".to_vec() };
+		let mut out = Vec::new();
+		print_vigenere_result(&result, &Alphabet::Letters, &OutputFormat::Text, &mut out).unwrap();
+		print_vigenere_result(&result, &Alphabet::Letters, &OutputFormat::Json, &mut out).unwrap();
+		print_vigenere_result(&result, &Alphabet::Letters, &OutputFormat::Csv, &mut out).unwrap();
+		let mut expected = Vec::new();
+		writeln!(expected, "Key length: 2\nKey: LE\nPlaintext: AB").unwrap();
+		writeln!(
+			expected,
+			"{{\"key_length\":2,\"key\":[11,4],\"key_text\":\"LE\",\"plaintext\":\"AB\"}}"
+		).unwrap();
+		writeln!(expected, "key_length,key,key_text,plaintext\n2,11 4,\"LE\",\"AB\"").unwrap();
+		assert_eq!(out, expected);
+	}
+}
@@ -0,0 +1,496 @@
+use std::convert::TryFrom;
+use std::ops::AddAssign;
+use std::hash::Hash;
+use std::io::Write;
+use std::io;
+use crate::analytics::is_printable;
+use crate::analytics::text_chi_squared;
+use crate::input::Endian;
+use crate::input::filter_input_vec;
+use crate::input::words_to_bytes;
+use crate::languages::ENGLISH;
+use crate::output::OutputFormat;
+use crate::output::words_to_text;
+use crate::output::write_json_array;
+use crate::output::write_json_float;
+use crate::output::write_json_string;
+use crate::output::write_csv_list;
+use crate::output::write_csv_string;
+use crate::types::NumBytes;
+
+// Number of best ranked key lengths which are solved
+const KEY_LENGTH_CANDIDATES: usize = 3;
+// Penalty per key word when keys of different lengths are compared, since
+// the shorter columns of a longer key fit the letter frequencies more closely
+const KEY_WORD_PENALTY: f64 = 5.0;
+// Number of best keys of every byte of a word which are combined into keys
+// of the whole word
+const LANE_KEYS: usize = 16;
+
+/// Normalized hamming distance between the blocks of one key length
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct KeyLengthDistance {
+	pub key_length: usize,
+	/// Average number of differing bits per word of neighbouring blocks
+	pub distance: f64,
+}
+
+/// Plaintext candidate of a single word key
+#[derive(PartialEq, Debug, Clone)]
+pub struct XorCandidate<T> {
+	pub key: T,
+	/// Share of printable ASCII words including tab and newlines
+	pub printable_ratio: f64,
+	/// Chi-squared statistic of the plaintext against english
+	pub score: f64,
+	/// Bytes of the plaintext words in the input byte order
+	pub plaintext: Vec<u8>,
+}
+
+/// Recovered repeating key and plaintext
+#[derive(PartialEq, Debug, Clone)]
+pub struct XorResult<T> {
+	pub key: Vec<T>,
+	/// Chi-squared statistic of the plaintext against english
+	pub score: f64,
+	pub plaintext: Vec<T>,
+}
+
+fn xor_word<T: Copy + Into<u128> + TryFrom<u128>>(word: T, key: T) -> T {
+	return match T::try_from(word.into() ^ key.into()) {
+		Ok(t) => t,
+		Err(_) => word,
+	};
+}
+
+/// Xor the input with the repeated key
+pub fn xor_decrypt<
+	T: Copy + Into<u128> + TryFrom<u128>
+>(vec: &[T], key: &[T]) -> Vec<T> {
+	return vec.iter().enumerate().map(
+		|(i, w)| xor_word(*w, key[i % key.len()])).collect();
+}
+
+// Shortest period of the key, a key like "KEYKEY" is reduced to "KEY"
+pub(crate) fn shortest_key<T: Copy + Eq>(key: Vec<T>) -> Vec<T> {
+	for period in 1..key.len() {
+		if key.len() % period == 0 &&
+				key.iter().enumerate().all(|(i, k)| *k == key[i % period]) {
+			return key[..period].to_vec();
+		}
+	}
+	return key;
+}
+
+/// Share of printable ASCII words including tab, line feed and carriage
+/// return, 0 for an empty input
+pub fn printable_ratio<T: Copy + Into<u128>>(vec: &[T]) -> f64 {
+	if vec.is_empty() {
+		return 0.0;
+	}
+	let printable = vec.iter().filter(|w| is_printable((**w).into())).count();
+	return (printable as f64) / (vec.len() as f64);
+}
+
+fn hamming_distance<T: Copy + Into<u128>>(a: &[T], b: &[T]) -> u32 {
+	return a.iter().zip(b.iter()).map(
+		|(x, y)| ((*x).into() ^ (*y).into()).count_ones()).sum();
+}
+
+/// Rank the key lengths up to max_key_length by the normalized hamming
+/// distance of all neighbouring blocks, smallest distance first.
+/// Key lengths with less than 2 blocks are not ranked.
+pub fn xor_key_lengths<
+	T: Copy + Into<u128>
+>(vec: &[T], max_key_length: usize) -> Vec<KeyLengthDistance> {
+	let mut result: Vec<KeyLengthDistance> = Vec::new();
+	for key_length in 1..max_key_length + 1 {
+		let blocks = vec.len() / key_length;
+		if blocks < 2 {
+			break;
+		}
+		let mut sum: u64 = 0;
+		for b in 0..blocks - 1 {
+			let start = b * key_length;
+			sum += u64::from(hamming_distance(
+				&vec[start..start + key_length],
+				&vec[start + key_length..start + 2 * key_length]));
+		}
+		let words = ((blocks - 1) * key_length) as f64;
+		result.push(KeyLengthDistance {
+			key_length: key_length,
+			distance: (sum as f64) / words,
+		});
+	}
+	result.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+	return result;
+}
+
+// Best keys of one byte of a word, the lane holds this byte of every word
+fn xor_lane_keys(lane: &[u8], top: usize) -> Vec<u8> {
+	let mut keys: Vec<(u8, f64)> = (0..=u8::MAX).map(
+		|k| (k, text_chi_squared(&xor_decrypt(lane, &[k]), &ENGLISH))).collect();
+	keys.sort_by(|a, b| a.1.total_cmp(&b.1));
+	return keys.iter().take(top).map(|k| k.0).collect();
+}
+
+/// Keys of one word with their plaintext score, best key first. The words
+/// are split into bytes in the given byte order and scored as text. Every
+/// byte of the key is solved on its own byte of the words, only the best
+/// keys of every byte are combined. Only words up to 2 bytes are supported.
+pub fn xor_single_keys<
+	T: Copy + Into<u128> + TryFrom<u128> + NumBytes
+>(vec: &[T], endian: &Endian) -> Result<Vec<(T, f64)>, io::Error> {
+	if T::BYTES > 2 {
+		return Err(io::Error::other("Xor key search supports only words up to 2 bytes"));
+	}
+	let num_bytes = usize::from(T::BYTES);
+	let bytes = words_to_bytes(vec, endian);
+	let lane_keys = match num_bytes {
+		1 => usize::from(u8::MAX) + 1,
+		_ => LANE_KEYS,
+	};
+	// Key bytes in the input byte order
+	let mut keys: Vec<Vec<u8>> = vec![Vec::new()];
+	for lane in 0..num_bytes {
+		let lane_vec = filter_input_vec(&bytes, num_bytes, lane)?;
+		let best = xor_lane_keys(&lane_vec, lane_keys);
+		keys = keys.iter().flat_map(|prefix| best.iter().map(move |k| {
+			let mut key = prefix.clone();
+			key.push(*k);
+			key
+		})).collect();
+	}
+	let mut result: Vec<(T, f64)> = Vec::with_capacity(keys.len());
+	for key_bytes in keys {
+		let plaintext = xor_decrypt(&bytes, &key_bytes);
+		let ordered: Vec<u8> = match endian {
+			Endian::Big => key_bytes,
+			Endian::Little => key_bytes.into_iter().rev().collect(),
+		};
+		let value = ordered.iter().fold(0_u128, |v, b| (v << 8) | u128::from(*b));
+		let key = match T::try_from(value) {
+			Ok(t) => t,
+			Err(_) => continue,
+		};
+		result.push((key, text_chi_squared(&plaintext, &ENGLISH)));
+	}
+	result.sort_by(|a, b| a.1.total_cmp(&b.1));
+	return Ok(result);
+}
+
+/// Xor the input with the keys of one word, the top candidates ranked by
+/// their english chi-squared are returned
+pub fn xor_single<
+	T: Copy + Into<u128> + TryFrom<u128> + NumBytes
+>(vec: &[T], endian: &Endian, top: usize) -> Result<Vec<XorCandidate<T>>, io::Error> {
+	let mut result: Vec<XorCandidate<T>> = Vec::with_capacity(top);
+	for (key, score) in xor_single_keys(vec, endian)?.into_iter().take(top) {
+		let plaintext = words_to_bytes(&xor_decrypt(vec, &[key]), endian);
+		result.push(XorCandidate {
+			key: key,
+			printable_ratio: printable_ratio(&plaintext),
+			score: score,
+			plaintext: plaintext,
+		});
+	}
+	return Ok(result);
+}
+
+/// Write the result of xor_single in the given format
+pub fn print_xor_single_result<
+	T: Copy + Into<u128> + std::fmt::Display, W: Write
+>(result: &Vec<XorCandidate<T>>, format: &OutputFormat, out: &mut W)
+		-> Result<(), io::Error> {
+	match format {
+		OutputFormat::Text => {
+			writeln!(out, "Candidates: {}", result.len())?;
+			for c in result {
+				writeln!(
+					out, "{}: {:.4}: {:.6}: {}", c.key, c.printable_ratio, c.score,
+					words_to_text(&c.plaintext))?;
+			}
+		},
+		OutputFormat::Json => {
+			write!(out, "{{\"candidates\":[")?;
+			for (i, c) in result.iter().enumerate() {
+				if i > 0 {
+					write!(out, ",")?;
+				}
+				write!(out, "{{\"key\":{},\"printable_ratio\":", c.key)?;
+				write_json_float(c.printable_ratio, out)?;
+				write!(out, ",\"score\":")?;
+				write_json_float(c.score, out)?;
+				write!(out, ",\"plaintext\":")?;
+				write_json_string(&words_to_text(&c.plaintext), out)?;
+				write!(out, "}}")?;
+			}
+			writeln!(out, "]}}")?;
+		},
+		OutputFormat::Csv => {
+			writeln!(out, "key,printable_ratio,score,plaintext")?;
+			for c in result {
+				write!(out, "{},{},{},", c.key, c.printable_ratio, c.score)?;
+				write_csv_string(&words_to_text(&c.plaintext), out)?;
+				writeln!(out)?;
+			}
+		},
+	};
+	return Ok(());
+}
+
+/// Solve a repeating-key xor. The best ranked key lengths and their divisors
+/// are split into columns which are solved as single word xor, the key with
+/// the best plaintext plus a penalty per key word wins. A key length > 0 is
+/// used instead of the ranking.
+/// Only 1 byte words are supported.
+pub fn xor_repeating<
+	T: Copy + AddAssign + Eq + Hash + From<u8> + Into<u128> + TryFrom<u128> + NumBytes
+>(vec: &[T], max_key_length: usize, key_length: usize)
+		-> Result<XorResult<T>, io::Error> {
+	if T::BYTES != 1 {
+		return Err(io::Error::other("Repeating-key xor needs 1 byte words"));
+	}
+	let key_lengths: Vec<usize> = match key_length {
+		0 => {
+			// A candidate is often a multiple of the key length, so its
+			// divisors are solved too
+			let mut lengths: Vec<usize> = xor_key_lengths(vec, max_key_length)
+				.iter().take(KEY_LENGTH_CANDIDATES)
+				.flat_map(|k| (1..k.key_length + 1).filter(move |d| k.key_length % d == 0))
+				.collect();
+			lengths.sort();
+			lengths.dedup();
+			lengths
+		},
+		l => vec![l],
+	};
+	let mut best: Option<XorResult<T>> = None;
+	for length in key_lengths {
+		let mut key: Vec<T> = Vec::with_capacity(length);
+		for column in 0..length {
+			let column_vec = filter_input_vec(vec, length, column)?;
+			key.push(xor_single_keys(&column_vec, &Endian::Big)?[0].0);
+		}
+		let key = shortest_key(key);
+		let plaintext = xor_decrypt(vec, &key);
+		let score = text_chi_squared(&plaintext, &ENGLISH);
+		let penalized = score + KEY_WORD_PENALTY * key.len() as f64;
+		if best.as_ref().map_or(
+				true, |b| penalized < b.score + KEY_WORD_PENALTY * b.key.len() as f64) {
+			best = Some(XorResult { key: key, score: score, plaintext: plaintext });
+		}
+	}
+	return match best {
+		Some(b) => Ok(b),
+		None => Err(io::Error::other("Input is too short to find a key length")),
+	};
+}
+
+/// Write the result of xor_repeating in the given format
+pub fn print_xor_repeating_result<
+	T: Copy + Into<u128> + std::fmt::Display, W: Write
+>(result: &XorResult<T>, format: &OutputFormat, out: &mut W) -> Result<(), io::Error> {
+	let key_text = words_to_text(&result.key);
+	let plaintext = words_to_text(&result.plaintext);
+	match format {
+		OutputFormat::Text => {
+			writeln!(out, "Key length: {}", result.key.len())?;
+			writeln!(out, "Key: {}", key_text)?;
+			writeln!(out, "Score: {:.6}", result.score)?;
+			writeln!(out, "Plaintext: {}", plaintext)?;
+		},
+		OutputFormat::Json => {
+			write!(out, "{{\"key_length\":{},\"key\":", result.key.len())?;
+			write_json_array(&result.key, out)?;
+			write!(out, ",\"key_text\":")?;
+			write_json_string(&key_text, out)?;
+			write!(out, ",\"score\":")?;
+			write_json_float(result.score, out)?;
+			write!(out, ",\"plaintext\":")?;
+			write_json_string(&plaintext, out)?;
+			writeln!(out, "}}")?;
+		},
+		OutputFormat::Csv => {
+			writeln!(out, "key_length,key,key_text,score,plaintext")?;
+			write!(out, "{},", result.key.len())?;
+			write_csv_list(&result.key, out)?;
+			write!(out, ",")?;
+			write_csv_string(&key_text, out)?;
+			write!(out, ",{},", result.score)?;
+			write_csv_string(&plaintext, out)?;
+			writeln!(out)?;
+		},
+	};
+	return Ok(());
+}
+
+#[cfg(test)]
+mod tests {
+	use std::io;
+	use crate::solvers::xor::shortest_key;
+	use crate::solvers::xor::xor_decrypt;
+	use crate::solvers::xor::xor_key_lengths;
+	use crate::solvers::xor::xor_single_keys;
+	use crate::solvers::xor::xor_single;
+	use crate::solvers::xor::printable_ratio;
+	use crate::solvers::xor::print_xor_single_result;
+	use crate::solvers::xor::XorCandidate;
+	use crate::solvers::xor::xor_repeating;
+	use crate::solvers::xor::print_xor_repeating_result;
+	use crate::solvers::xor::XorResult;
+	use std::io::Write;
+	use crate::input::convert_vec;
+	use crate::input::Endian;
+	use crate::output::OutputFormat;
+
+	const PLAINTEXT: &[u8] = b"Burning 'em, if you ain't quick and nimble\n\
+		I go crazy when I hear a cymbal. It was the best of times, it was the \
+		worst of times, it was the age of wisdom, it was the age of foolishness, \
+		it was the epoch of belief, it was the epoch of incredulity, it was the \
+		season of Light, it was the season of Darkness, it was the spring of hope.";
+
+	#[test]
+	fn xor_decrypt_u8() {
+		assert_eq!(
+			xor_decrypt(&[0x0b_u8, 0x36, 0x37, 0x27], &b"ICE"[..]),
+			b"Burn");
+	}
+
+	#[test]
+	fn shortest_key_periodic() {
+		assert_eq!(shortest_key(b"KEYKEY".to_vec()), b"KEY");
+		assert_eq!(shortest_key(b"KEYKE".to_vec()), b"KEYKE");
+		assert_eq!(shortest_key(b"AAAA".to_vec()), b"A");
+	}
+
+	#[test]
+	fn xor_key_lengths_u8() {
+		let ciphertext = xor_decrypt(PLAINTEXT, &b"SECRET"[..]);
+		let ranking = xor_key_lengths(&ciphertext, 20);
+		assert_eq!(ranking.len(), 20);
+		assert_eq!(ranking[0].key_length % 6, 0);
+		assert!(xor_key_lengths(&[1_u8], 20).is_empty());
+	}
+
+	#[test]
+	fn xor_single_keys_u16() -> Result<(), io::Error> {
+		let ciphertext = xor_decrypt(PLAINTEXT, &[0x5a, 0x13]);
+		let big = convert_vec::<u16>(&ciphertext, &Endian::Big)?;
+		let keys = xor_single_keys(&big, &Endian::Big)?;
+		assert_eq!(keys.len(), 256);
+		assert_eq!(keys[0].0, 0x5a13);
+		let little = convert_vec::<u16>(&ciphertext, &Endian::Little)?;
+		assert_eq!(xor_single_keys(&little, &Endian::Little)?[0].0, 0x135a);
+		let err = xor_single_keys(&[1_u32], &Endian::Big).unwrap_err();
+		assert_eq!(err.to_string(), "Xor key search supports only words up to 2 bytes");
+		return Ok(());
+	}
+
+	#[test]
+	fn printable_ratio_test() {
+		assert_eq!(printable_ratio(&b"ab\n\x00"[..]), 0.75);
+		assert_eq!(printable_ratio(&[0x41_u16, 0x4100]), 0.5);
+		assert_eq!(printable_ratio::<u8>(&[]), 0.0);
+	}
+
+	#[test]
+	fn xor_single_u8() -> Result<(), io::Error> {
+		let ciphertext = xor_decrypt(PLAINTEXT, &[0x5a]);
+		let result = xor_single(&ciphertext, &Endian::Big, 3)?;
+		assert_eq!(result.len(), 3);
+		assert_eq!(result[0].key, 0x5a);
+		assert_eq!(result[0].printable_ratio, 1.0);
+		assert_eq!(result[0].plaintext, PLAINTEXT);
+		assert!(result[0].score < result[1].score);
+		assert_eq!(xor_single(&ciphertext, &Endian::Little, 300)?.len(), 256);
+		return Ok(());
+	}
+
+	#[test]
+	fn xor_single_u16() -> Result<(), io::Error> {
+		let ciphertext = xor_decrypt(PLAINTEXT, &[0x5a, 0x13]);
+		let words = convert_vec::<u16>(&ciphertext, &Endian::Little)?;
+		let result = xor_single(&words, &Endian::Little, 2)?;
+		assert_eq!(result[0].key, 0x135a);
+		assert_eq!(result[0].printable_ratio, 1.0);
+		assert_eq!(result[0].plaintext, PLAINTEXT);
+		return Ok(());
+	}
+
+	#[test]
+	fn print_xor_single_result_test() {
+		let result = vec![
+			XorCandidate { key: 32_u8, printable_ratio: 1.0, score: 0.5, plaintext: b"Hi".to_vec() },
+			XorCandidate { key: 1_u8, printable_ratio: 0.5, score: 2.0, plaintext: vec![0, 0x41] },
+		];
+		let mut out = Vec::new();
+		print_xor_single_result(&result, &OutputFormat::Text, &mut out).unwrap();
+		print_xor_single_result(&result, &OutputFormat::Json, &mut out).unwrap();
+		print_xor_single_result(&result, &OutputFormat::Csv, &mut out).unwrap();
+		let mut expected = Vec::new();
+		writeln!(
+			expected,
+			"Candidates: 2\n32: 1.0000: 0.500000: Hi\n1: 0.5000: 2.000000: \\x00A").unwrap();
+		writeln!(
+			expected,
+			"{{\"candidates\":[{{\"key\":32,\"printable_ratio\":1,\"score\":0.5,\
+			\"plaintext\":\"Hi\"}},{{\"key\":1,\"printable_ratio\":0.5,\"score\":2,\
+			\"plaintext\":\"\\\\x00A\"}}]}}").unwrap();
+		writeln!(
+			expected,
+			"key,printable_ratio,score,plaintext\n32,1,0.5,\"Hi\"\n1,0.5,2,\"\\x00A\"").unwrap();
+		assert_eq!(out, expected);
+	}
+
+	#[test]
+	fn xor_repeating_u8() -> Result<(), io::Error> {
+		let ciphertext = xor_decrypt(PLAINTEXT, &b"ICEKEY"[..]);
+		let result = xor_repeating(&ciphertext, 20, 0)?;
+		assert_eq!(result.key, b"ICEKEY");
+		assert_eq!(result.plaintext, PLAINTEXT);
+		let fixed = xor_repeating(&ciphertext, 20, 6)?;
+		assert_eq!(fixed, result);
+		return Ok(());
+	}
+
+	#[test]
+	fn xor_repeating_key_length_1_candidate() -> Result<(), io::Error> {
+		// Every key length is a multiple of the candidate 1
+		let ciphertext = xor_decrypt(PLAINTEXT, &b"lqudsa"[..]);
+		let ranking: Vec<usize> = xor_key_lengths(&ciphertext, 20)
+			.iter().take(3).map(|k| k.key_length).collect();
+		assert_eq!(ranking, vec![18, 6, 1]);
+		let result = xor_repeating(&ciphertext, 20, 0)?;
+		assert_eq!(result.key, b"lqudsa");
+		assert_eq!(result.plaintext, PLAINTEXT);
+		return Ok(());
+	}
+
+	#[test]
+	fn xor_repeating_errors() {
+		let err = xor_repeating(&[1_u16, 2, 3], 20, 0).unwrap_err();
+		assert_eq!(err.to_string(), "Repeating-key xor needs 1 byte words");
+		let err = xor_repeating(&[1_u8], 20, 0).unwrap_err();
+		assert_eq!(err.to_string(), "Input is too short to find a key length");
+	}
+
+	#[test]
+	fn print_xor_repeating_result_test() {
+		let result = XorResult { key: b"IC".to_vec(), score: 0.5, plaintext: b"AB".to_vec() };
+		let mut out = Vec::new();
+		print_xor_repeating_result(&result, &OutputFormat::Text, &mut out).unwrap();
+		print_xor_repeating_result(&result, &OutputFormat::Json, &mut out).unwrap();
+		print_xor_repeating_result(&result, &OutputFormat::Csv, &mut out).unwrap();
+		let mut expected = Vec::new();
+		writeln!(expected, "Key length: 2\nKey: IC\nScore: 0.500000\nPlaintext: AB").unwrap();
+		writeln!(
+			expected,
+			"{{\"key_length\":2,\"key\":[73,67],\"key_text\":\"IC\",\"score\":0.5,\
+			\"plaintext\":\"AB\"}}").unwrap();
+		writeln!(
+			expected,
+			"key_length,key,key_text,score,plaintext\n2,73 67,\"IC\",0.5,\"AB\"").unwrap();
+		assert_eq!(out, expected);
+	}
+}
@@ -1,9 +1,20 @@
+/// Size in bytes of the word types
 pub trait NumBytes {
 	const BYTES: u8;
+
+	/// The bytes as words without copying them, only possible for single
+	/// byte words
+	fn from_bytes(_bytes: &[u8]) -> Option<&[Self]> where Self: Sized {
+		return None;
+	}
 }
 
 impl NumBytes for u8 {
 	const BYTES: u8 = 1;
+
+	fn from_bytes(bytes: &[u8]) -> Option<&[u8]> {
+		return Some(bytes);
+	}
 }
 
 impl NumBytes for u16 {
@@ -35,4 +46,10 @@ mod tests {
 		assert_eq!(u64::BYTES, 8);
 		assert_eq!(u128::BYTES, 16);
 	}
+
+	#[test]
+	fn from_bytes() {
+		assert_eq!(u8::from_bytes(&[1, 2]), Some(&[1_u8, 2][..]));
+		assert_eq!(u16::from_bytes(&[1, 2]), None);
+	}
 }